@@ -0,0 +1,106 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use minautomata::game::Game;
+use minautomata::kinds::ParticleKind;
+
+const SIZES: [usize; 3] = [64, 128, 256];
+
+/// Every scene plays out the same from run to run.
+const SEED: u64 = 26;
+
+/// Builds a game of the given canvas size with some particles in it.
+type Scene = fn(usize) -> Game;
+
+/// Nothing but the concrete floor.
+fn empty(size: usize) -> Game {
+    Game::with_seed(size, SEED)
+}
+
+/// Bottom half of the game area filled with water.
+fn half_full_water(size: usize) -> Game {
+    let mut g = Game::with_seed(size, SEED);
+    let n_rows = g.get_n_rows();
+    for y in (n_rows / 2)..(n_rows - 1) {
+        for x in 0..g.get_n_cols() {
+            g.put_particle(x, y, ParticleKind::Water);
+        }
+    }
+    g
+}
+
+/// Top half of the game area filled with salt, ready to fall.
+fn salt_avalanche(size: usize) -> Game {
+    let mut g = Game::with_seed(size, SEED);
+    for y in 0..(g.get_n_rows() / 2) {
+        for x in 0..g.get_n_cols() {
+            g.put_particle(x, y, ParticleKind::Salt);
+        }
+    }
+    g
+}
+
+/// A row of water-fed cornucopias along the top of the game area.
+fn cornucopia_flood(size: usize) -> Game {
+    let mut g = Game::with_seed(size, SEED);
+    for x in (0..g.get_n_cols()).step_by(8) {
+        g.put_particle(x, 0, ParticleKind::Cornucopia);
+        g.put_particle(x, 1, ParticleKind::Water);
+    }
+    // let the flood get going before measuring
+    for _ in 0..50 {
+        g.update();
+    }
+    g
+}
+
+fn bench_update(c: &mut Criterion) {
+    let scenes: [(&str, Scene); 4] = [
+        ("empty", empty),
+        ("half_full_water", half_full_water),
+        ("salt_avalanche", salt_avalanche),
+        ("cornucopia_flood", cornucopia_flood),
+    ];
+
+    let mut group = c.benchmark_group("update");
+    for (name, scene) in scenes {
+        for size in SIZES {
+            group.bench_with_input(BenchmarkId::new(name, size), &size, |b, &size| {
+                b.iter_batched(|| scene(size), |mut g| {
+                    g.update();
+                    // hand the game back so dropping it isn't timed
+                    g
+                }, BatchSize::LargeInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_neighbours(c: &mut Criterion) {
+    let mut group = c.benchmark_group("neighbours");
+    for size in SIZES {
+        let g = half_full_water(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &g, |b, g| {
+            b.iter(|| {
+                for y in 0..g.get_n_rows() {
+                    for x in 0..g.get_n_cols() {
+                        black_box(g.get_neighbours(x, y));
+                    }
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+    for size in SIZES {
+        let mut g = half_full_water(size);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| g.render()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_update, bench_neighbours, bench_render);
+criterion_main!(benches);
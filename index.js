@@ -9,12 +9,20 @@ function after_init(w) {
   r.then(r => after_load(r, w)).catch(console.error);
 }
 
+var kind_names = [];
+var rust_kinds = {};
+var policy_names = [];
+
 function after_load(rust, wasm) {
+  kind_names = Object.keys(rust.ParticleKind).filter(k => isNaN(k));
+  rust_kinds = rust.ParticleKind;
+  policy_names = Object.keys(rust.ConflictPolicy).filter(k => isNaN(k));
   let game = new rust.Game();
   start(game, wasm);
 }
 
 const container = document.getElementById("container");
+const hud = document.getElementById("hud");
 const canvasElement = document.querySelector("canvas");
 var canvas_size = 20;
 
@@ -37,7 +45,9 @@ function start(game, wasm) {
     canvasElement.addEventListener("mousedown", (ev)=>start_painting(game, ev));
     canvasElement.addEventListener("mouseup", stop_painting);
     canvasElement.addEventListener("mousemove", mouse_move);
+    canvasElement.addEventListener("contextmenu", (ev)=>ev.preventDefault());
     window.addEventListener("resize", update_window_size);
+    document.addEventListener("keydown", (ev)=>key_down(game, ev));
     update_window_size();
 
     // Clear the canvas
@@ -51,6 +61,12 @@ var painting = false;
 var me;
 
 function start_painting(game, e) {
+  // right click flips switches rather than painting
+  if (e.button == 2) {
+    let [x, y] = cell_at(e);
+    game.toggle(x, y);
+    return;
+  }
   painting = true;
   me = e;
   paint(game, me);
@@ -65,20 +81,23 @@ function mouse_move(e) {
 }
 
 
+// position of the mouse in canvas cells
+function cell_at(e) {
+  var rect = e.target.getBoundingClientRect();
+  var x = e.clientX - rect.left; //x position within the element.
+  var y = e.clientY - rect.top;  //y position within the element.
+
+  let w = window.innerWidth;
+  let h = window.innerHeight;
+  let s = (h < w ? h : w);
+  let cs = canvas_size;
+  return [Math.floor(x/s*cs), Math.floor(y/s*cs)];
+}
+
 function paint(game, e) {
   me = e;
   if (painting) {
-    var rect = me.target.getBoundingClientRect();
-    var x = me.clientX - rect.left; //x position within the element.
-    var y = me.clientY - rect.top;  //y position within the element.
-
-    let w = window.innerWidth;
-    let h = window.innerHeight;
-    let s = (h < w ? h : w);
-    let cs = canvas_size;
-    x = Math.floor(x/s*cs);
-    y = Math.floor(y/s*cs);
-
+    let [x, y] = cell_at(me);
     game.clicked(x, y);
 
     setTimeout(()=> {
@@ -87,6 +106,91 @@ function paint(game, e) {
   }
 }
 
+// space: pause/resume, period: advance one tick, +/-: change speed,
+// r: dump a recording of the session to the console, s: paint sources of
+// the current brush, c: paint cornucopias of the current brush, [/]:
+// halve/double the rate sources emit at, l: cycle through life rules, m:
+// switch between in-place and synchronous updates, k: cycle through the
+// ways synchronous mode settles clashes, g: turn gravity by 45 degrees, z:
+// switch gravity off and on, w: paint wind (again to turn it, or to paint
+// still air), u: a breeze across the whole world, v: a whirlwind in the
+// middle, o: show the wind
+const life_rules = ["B3/S23", "B36/S23", "B2/S", "B2/S/C3"];
+const wind_brushes = [[1, 0], [0, -1], [-1, 0], [0, 1], [0, 0]];
+var wind_brush = -1;
+var breeze = false;
+
+function key_down(game, e) {
+  switch (e.key) {
+    case " ":
+      game.toggle_paused();
+      break;
+    case ".":
+      game.step(1);
+      break;
+    case "+":
+    case "=":
+      game.set_ticks_per_frame(game.get_ticks_per_frame() + 1);
+      break;
+    case "-":
+      game.set_ticks_per_frame(Math.max(1, game.get_ticks_per_frame() - 1));
+      break;
+    case "r":
+      console.log(game.get_recording());
+      break;
+    case "s":
+      game.set_source(game.get_brush(), game.get_source_rate());
+      game.set_brush(rust_kinds.Source);
+      break;
+    case "c":
+      game.set_cornucopia(game.get_brush(), 1.0, 0, 1, rust_kinds.Background);
+      game.set_brush(rust_kinds.Cornucopia);
+      break;
+    case "[":
+      game.set_source(game.get_source_kind(), game.get_source_rate() / 2);
+      break;
+    case "]":
+      game.set_source(game.get_source_kind(), Math.min(1, game.get_source_rate() * 2));
+      break;
+    case "l":
+      let next = (life_rules.indexOf(game.get_life_rule()) + 1) % life_rules.length;
+      game.set_life_rule(life_rules[next]);
+      console.log(`life rule ${life_rules[next]}`);
+      break;
+    case "m":
+      game.set_synchronous(!game.is_synchronous());
+      break;
+    case "k":
+      game.set_conflict_policy((game.get_conflict_policy() + 1) % policy_names.length);
+      break;
+    case "g": {
+      let [x, y] = [game.get_gravity_x(), game.get_gravity_y()];
+      game.set_gravity(x - y, x + y, game.get_gravity_strength());
+      break;
+    }
+    case "z":
+      game.set_gravity(game.get_gravity_x(), game.get_gravity_y(), game.get_gravity_strength() > 0 ? 0 : 1);
+      break;
+    case "w":
+      wind_brush = game.is_painting_wind() ? (wind_brush + 1) % wind_brushes.length : 0;
+      game.set_wind_brush(...wind_brushes[wind_brush]);
+      break;
+    case "u":
+      breeze = !breeze;
+      game.set_uniform_wind(breeze ? 0.3 : 0, 0);
+      break;
+    case "v":
+      game.add_vortex(Math.floor(canvas_size / 2), Math.floor(canvas_size / 2), Math.floor(canvas_size / 4), 1);
+      break;
+    case "o":
+      game.set_show_wind(!game.is_showing_wind());
+      break;
+    default:
+      return;
+  }
+  e.preventDefault();
+}
+
 function update_window_size() {
   let w = window.innerWidth;
   let h = window.innerHeight;
@@ -96,6 +200,25 @@ function update_window_size() {
 }
 
 
+function update_hud(game) {
+  const stats = game.get_stats();
+  if (stats.tick % 10 != 0 && !game.is_paused()) {
+    stats.free();
+    return;
+  }
+
+  const counts = stats.counts;
+  let lines = [
+    `tick ${stats.tick} (${stats.tick_duration_ms.toFixed(2)} ms)${game.is_paused() ? " [paused]" : ""}${game.is_synchronous() ? ` [synchronous, ${policy_names[game.get_conflict_policy()]}]` : ""}`,
+    `active ${stats.active}  moves ${stats.moves}  pops ${stats.pops}  grows ${stats.grows}  reactions ${stats.reactions}  explosions ${stats.explosions}  conflicts ${stats.conflicts}  overwrites ${stats.overwrites}`,
+  ];
+  for (let i = 1; i < kind_names.length; i++) {
+    lines.push(`${kind_names[i]}: ${counts[i]}`);
+  }
+  hud.textContent = lines.join("\n");
+  stats.free();
+}
+
 function update(game, wasm, canvasImageData, canvasContext) {
 
     // update container size
@@ -118,6 +241,8 @@ function update(game, wasm, canvasImageData, canvasContext) {
     canvasContext.clearRect(0, 0, canvasElement.width, canvasElement.height);
     canvasContext.putImageData(canvasImageData, 0, 0);
 
+    update_hud(game);
+
     // call update again in x ms
     setTimeout(() => {
       update(game, wasm, canvasImageData, canvasContext)
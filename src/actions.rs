@@ -5,6 +5,60 @@ pub enum Action {
     Pop,
     StayPut,
     MoveInto{x: i32, y: i32},
+    /// Move into the neighbouring cell, and move whatever was there into the
+    /// cell this particle left.
+    SwapWith{x: i32, y: i32},
     GrowInto{x: i32, y: i32, kind: ParticleKind},
-    Become(ParticleKind)
-}
\ No newline at end of file
+    /// Split in two, filling the neighbouring cell with a copy of this
+    /// particle, state and all. See `Particle::divide`.
+    Divide{x: i32, y: i32},
+    /// Turn into a particle of another kind; nothing happens if the particle
+    /// is already of that kind.
+    Become(ParticleKind),
+    /// React with the neighbouring particle: this particle becomes `this` (as
+    /// for `Become`) and the neighbour is replaced by a new particle of kind
+    /// `that`.
+    React{x: i32, y: i32, this: ParticleKind, that: ParticleKind},
+    /// Freeze the neighbouring particle into ice where it is. Unlike a
+    /// reaction, the ice keeps the neighbour's temperature, or freezing
+    /// point if it was warmer than that.
+    Freeze{x: i32, y: i32},
+    /// Blow up, destroying or flinging everything within `radius` cells once
+    /// every particle has had its turn this tick.
+    Explode{radius: u32}
+}
+
+impl Action {
+    /// The same action with its direction mirrored top-to-bottom, for rules
+    /// written as if the world were upside down.
+    pub fn flipped_vertically(self) -> Action {
+        self.turned(|x, y| (x, -y))
+    }
+
+    /// The same action with the offset to the neighbour it acts on, if any,
+    /// changed by `turn`.
+    pub fn turned<F: Fn(i32, i32) -> (i32, i32)>(self, turn: F) -> Action {
+        match self {
+            Action::MoveInto{x, y} => { let (x, y) = turn(x, y); Action::MoveInto{x, y} },
+            Action::SwapWith{x, y} => { let (x, y) = turn(x, y); Action::SwapWith{x, y} },
+            Action::GrowInto{x, y, kind} => { let (x, y) = turn(x, y); Action::GrowInto{x, y, kind} },
+            Action::Divide{x, y} => { let (x, y) = turn(x, y); Action::Divide{x, y} },
+            Action::React{x, y, this, that} => { let (x, y) = turn(x, y); Action::React{x, y, this, that} },
+            Action::Freeze{x, y} => { let (x, y) = turn(x, y); Action::Freeze{x, y} },
+            other => other
+        }
+    }
+
+    /// Offset of the neighbouring cell this action changes, if any.
+    pub fn target(&self) -> Option<(i32, i32)> {
+        match *self {
+            Action::MoveInto{x, y}
+            | Action::SwapWith{x, y}
+            | Action::GrowInto{x, y, ..}
+            | Action::Divide{x, y}
+            | Action::React{x, y, ..}
+            | Action::Freeze{x, y} => Some((x, y)),
+            _ => None
+        }
+    }
+}
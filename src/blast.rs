@@ -0,0 +1,68 @@
+/// Chance that a cell emptied by a blast is left burning.
+pub const FIRE_CHANCE: f32 = 0.4;
+
+/// Heat added by a blast to cells at its centre; less further out.
+pub const BLAST_HEAT: f32 = 400.0;
+
+/// An explosion centred on a cell, queued up during a tick and set off once
+/// every particle has had its turn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blast {
+    pub x: usize,
+    pub y: usize,
+    pub radius: u32
+}
+
+impl Blast {
+
+    pub fn new(x: usize, y: usize, radius: u32) -> Blast {
+        Blast{x, y, radius}
+    }
+
+    /// Strength of the blast at an offset from its centre, from 1 at the
+    /// centre falling to 0 just beyond its radius.
+    pub fn power(&self, dx: i32, dy: i32) -> f32 {
+        let d = ((dx * dx + dy * dy) as f32).sqrt();
+        (1.0 - d / (self.radius + 1) as f32).max(0.0)
+    }
+
+    /// Every cell within the blast radius inside an `n_cols` by `n_rows`
+    /// grid, other than the centre, with the blast's power there. Ordered
+    /// furthest first, so that flinging the outer cells makes room for the
+    /// inner ones.
+    pub fn cells(&self, n_cols: usize, n_rows: usize) -> Vec<(usize, usize, f32)> {
+        let r = self.radius as i32;
+        let mut cells = Vec::new();
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let (x, y) = (self.x as i32 + dx, self.y as i32 + dy);
+                if (dx, dy) == (0, 0) || dx * dx + dy * dy > r * r
+                    || x < 0 || y < 0 || x as usize >= n_cols || y as usize >= n_rows {
+                    continue;
+                }
+                cells.push((x as usize, y as usize, self.power(dx, dy)));
+            }
+        }
+        cells.sort_by(|a, b| a.2.total_cmp(&b.2));
+        cells
+    }
+
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_blast_cells() {
+        let b = Blast::new(0, 5, 2);
+        let cells = b.cells(10, 10);
+        // half a disc, clipped by the left edge
+        assert_eq!(cells.len(), 8);
+        assert!(cells.iter().all(|&(x, _, _)| x <= 2));
+        assert!(cells.windows(2).all(|w| w[0].2 <= w[1].2));
+        assert_eq!(cells[cells.len() - 1].2, b.power(1, 0));
+    }
+
+}
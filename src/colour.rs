@@ -8,7 +8,7 @@ pub struct Colour {
 
 impl Colour {
     pub fn new(r: u8, g: u8, b: u8) -> Colour {
-        Colour{r:r, g:g, b:b}
+        Colour{r, g, b}
     }
 
     pub fn as_uarr(&self) -> [u8; 4] {
@@ -25,6 +25,12 @@ impl Colour {
     pub fn i_eq(&self, i: i32) -> bool {
         i == self.as_i()
     }
+
+    /// This colour with `amount` (0 to 1) of `other` mixed in.
+    pub fn mix(&self, other: &Colour, amount: f32) -> Colour {
+        let m = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+        Colour{r: m(self.r, other.r), g: m(self.g, other.g), b: m(self.b, other.b)}
+    }
 }
 
 // Colours
@@ -38,6 +44,39 @@ pub const RED: Colour = Colour{r: 255, g: 0, b: 0};
 pub const GREEN: Colour = Colour{r: 0, g: 255, b: 0};
 pub const BLUE: Colour = Colour{r: 0, g: 0, b: 255};
 
+pub const SAND: Colour = Colour{r: 194, g: 178, b: 128};
+pub const BRINE: Colour = Colour{r: 96, g: 160, b: 255};
+pub const BROWN: Colour = Colour{r: 120, g: 72, b: 32};
+pub const ORANGE: Colour = Colour{r: 255, g: 128, b: 0};
+pub const YELLOW: Colour = Colour{r: 255, g: 224, b: 64};
+pub const SMOKE: Colour = Colour{r: 48, g: 48, b: 48};
+pub const STEAM: Colour = Colour{r: 208, g: 224, b: 240};
+pub const ASH: Colour = Colour{r: 160, g: 152, b: 144};
+pub const ICE: Colour = Colour{r: 160, g: 220, b: 255};
+pub const LAVA: Colour = Colour{r: 176, g: 40, b: 16};
+pub const STONE: Colour = Colour{r: 96, g: 88, b: 84};
+pub const OIL: Colour = Colour{r: 72, g: 56, b: 24};
+pub const ACID: Colour = Colour{r: 128, g: 255, b: 32};
+pub const GLASS: Colour = Colour{r: 200, g: 232, b: 232};
+pub const GUNPOWDER: Colour = Colour{r: 56, g: 56, b: 64};
+pub const TNT: Colour = Colour{r: 200, g: 32, b: 48};
+pub const SOIL: Colour = Colour{r: 92, g: 64, b: 40};
+pub const SEED: Colour = Colour{r: 176, g: 140, b: 64};
+pub const STEM: Colour = Colour{r: 56, g: 128, b: 40};
+pub const WILTED: Colour = Colour{r: 140, g: 128, b: 48};
+pub const WIRE: Colour = Colour{r: 184, g: 115, b: 51};
+pub const BATTERY: Colour = Colour{r: 32, g: 96, b: 64};
+pub const HEATER: Colour = Colour{r: 128, g: 32, b: 24};
+pub const LAMP: Colour = Colour{r: 96, g: 88, b: 48};
+pub const SWITCH_ON: Colour = Colour{r: 96, g: 200, b: 96};
+pub const SWITCH_OFF: Colour = Colour{r: 64, g: 72, b: 64};
+pub const ELECTRON_HEAD: Colour = Colour{r: 128, g: 200, b: 255};
+pub const ELECTRON_TAIL: Colour = Colour{r: 255, g: 96, b: 64};
+pub const SOURCE: Colour = Colour{r: 64, g: 160, b: 160};
+pub const DRAIN: Colour = Colour{r: 24, g: 16, b: 40};
+pub const LIFE: Colour = Colour{r: 224, g: 255, b: 224};
+pub const LIFE_DYING: Colour = Colour{r: 96, g: 128, b: 160};
+
 
 
 #[allow(dead_code,unused_imports)]
@@ -51,4 +90,11 @@ mod tests {
         assert_eq!(255 << 16, red.as_i());
     }
 
+    #[test]
+    pub fn test_mix() {
+        assert!(RED.mix(&BLUE, 0.0).i_eq(RED.as_i()));
+        assert!(RED.mix(&BLUE, 1.0).i_eq(BLUE.as_i()));
+        assert!(BLACK.mix(&WHITE, 0.5).i_eq(Colour::new(128, 128, 128).as_i()));
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,112 @@
+use wasm_bindgen::prelude::*;
+
+use super::kinds::ParticleKind;
+use super::rng::Rng;
+
+/// How to choose between particles which, in synchronous mode, decided to
+/// change the same cell in the same tick. The winner acts and the rest stay
+/// put.
+#[wasm_bindgen]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum ConflictPolicy {
+    /// The particle scanned first, top to bottom and left to right, wins.
+    #[default]
+    FirstWins,
+    /// Any of them might win.
+    Random,
+    /// The densest particle wins.
+    Heaviest,
+    /// The particle with the most momentum, its density times its speed,
+    /// wins.
+    Momentum
+}
+
+impl ConflictPolicy {
+
+    pub const ALL: [ConflictPolicy; 4] = [
+        ConflictPolicy::FirstWins,
+        ConflictPolicy::Random,
+        ConflictPolicy::Heaviest,
+        ConflictPolicy::Momentum
+    ];
+
+    /// Look up a policy by its name, as printed by `Debug`.
+    pub fn from_name(name: &str) -> Option<ConflictPolicy> {
+        ConflictPolicy::ALL.iter().copied().find(|p| format!("{:?}", p) == name)
+    }
+
+    /// The order in which to grant `bids`, which are listed in scan order.
+    /// Each bid is granted unless one granted before it claimed the same
+    /// cell. Ties go to the bid scanned first.
+    pub fn order(self, bids: &[Bid], rng: &mut Rng) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..bids.len()).collect();
+        let mut by = |weight: fn(&Bid) -> f32| {
+            order.sort_by(|a, b| weight(&bids[*b]).total_cmp(&weight(&bids[*a])));
+        };
+        match self {
+            ConflictPolicy::FirstWins => (),
+            ConflictPolicy::Random => rng.shuffle(&mut order),
+            ConflictPolicy::Heaviest => by(|b| b.kind.density()),
+            ConflictPolicy::Momentum => by(Bid::momentum)
+        }
+        order
+    }
+}
+
+/// A particle's claim on the cells its action would change.
+#[derive(Copy, Clone, Debug)]
+pub struct Bid {
+    pub kind: ParticleKind,
+    /// How far the particle would move, if at all.
+    pub offset: (i32, i32),
+    /// The particle's velocity, in cells per tick.
+    pub velocity: (f32, f32)
+}
+
+impl Bid {
+
+    pub fn momentum(&self) -> f32 {
+        let dx = self.offset.0 as f32 + self.velocity.0;
+        let dy = self.offset.1 as f32 + self.velocity.1;
+        self.kind.density() * (dx*dx + dy*dy).sqrt()
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    fn bids() -> Vec<Bid> {
+        vec![
+            Bid{kind: ParticleKind::Sand, offset: (1, 1), velocity: (0.0, 0.0)},
+            Bid{kind: ParticleKind::Water, offset: (0, 1), velocity: (0.0, 0.0)},
+            Bid{kind: ParticleKind::Salt, offset: (0, 1), velocity: (0.0, 0.0)}
+        ]
+    }
+
+    #[test]
+    pub fn test_orders() {
+        let mut rng = Rng::new(47);
+        assert_eq!(ConflictPolicy::FirstWins.order(&bids(), &mut rng), vec![0, 1, 2]);
+        assert_eq!(ConflictPolicy::Heaviest.order(&bids(), &mut rng), vec![2, 0, 1]);
+        // diagonal sand carries more momentum than falling salt
+        assert_eq!(ConflictPolicy::Momentum.order(&bids(), &mut rng), vec![0, 2, 1]);
+        // unless the water is already falling fast
+        let mut fast = bids();
+        fast[1].velocity = (0.0, 3.0);
+        assert_eq!(ConflictPolicy::Momentum.order(&fast, &mut rng), vec![1, 0, 2]);
+        let mut random = ConflictPolicy::Random.order(&bids(), &mut rng);
+        random.sort();
+        assert_eq!(random, vec![0, 1, 2]);
+    }
+
+    #[test]
+    pub fn test_names() {
+        for p in ConflictPolicy::ALL {
+            assert_eq!(ConflictPolicy::from_name(&format!("{:?}", p)), Some(p));
+        }
+        assert_eq!(ConflictPolicy::from_name("Politest"), None);
+    }
+}
@@ -0,0 +1,98 @@
+use super::kinds::ParticleKind;
+
+/// Electrical state of a cell, Wireworld-style: a pulse is a head followed
+/// by a tail, and the tail stops the pulse flowing backwards.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Charge {
+    #[default]
+    None,
+    Head,
+    Tail
+}
+
+/// Move pulses along conductors for one tick, returning how many heads
+/// there are afterwards.
+///
+/// `kinds`, `conducts` and `charges` describe a grid `n_cols` wide, row by
+/// row. As in Wireworld, heads become tails, tails die away, and a
+/// conductor with one or two heads among its eight neighbours becomes a
+/// head. Batteries always count as heads. Every cell is worked out from
+/// the charges at the start of the tick, so pulses travel one cell per tick
+/// whichever way they go.
+pub fn propagate(kinds: &[ParticleKind], conducts: &[bool], charges: &mut [Charge], n_cols: usize) -> usize {
+    let before = charges.to_vec();
+    let n_rows = charges.len() / n_cols;
+    let mut heads = 0;
+    for idx in 0..charges.len() {
+        charges[idx] = if kinds[idx] == ParticleKind::Battery {
+            Charge::Head
+        }
+        else if !conducts[idx] {
+            Charge::None
+        }
+        else {
+            match before[idx] {
+                Charge::Head => Charge::Tail,
+                Charge::Tail => Charge::None,
+                Charge::None => {
+                    let (x, y) = ((idx % n_cols) as i32, (idx / n_cols) as i32);
+                    let mut n = 0;
+                    for dy in -1..=1 {
+                        for dx in -1..=1 {
+                            let (nx, ny) = (x + dx, y + dy);
+                            if (dx, dy) != (0, 0) && nx >= 0 && ny >= 0 && (nx as usize) < n_cols && (ny as usize) < n_rows
+                                && before[ny as usize * n_cols + nx as usize] == Charge::Head {
+                                n += 1;
+                            }
+                        }
+                    }
+                    if n == 1 || n == 2 { Charge::Head } else { Charge::None }
+                }
+            }
+        };
+        if charges[idx] == Charge::Head {
+            heads += 1;
+        }
+    }
+    heads
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_pulse_travels_along_wire() {
+        let kinds = [ParticleKind::Wire; 5];
+        let conducts = [true; 5];
+        let mut charges = [Charge::None; 5];
+        charges[0] = Charge::Tail;
+        charges[1] = Charge::Head;
+        for i in 2..5 {
+            propagate(&kinds, &conducts, &mut charges, 5);
+            assert_eq!(charges[i], Charge::Head);
+            assert_eq!(charges[i - 1], Charge::Tail);
+            assert_eq!(charges[i - 2], Charge::None);
+        }
+        propagate(&kinds, &conducts, &mut charges, 5);
+        assert_eq!(propagate(&kinds, &conducts, &mut charges, 5), 0);
+    }
+
+    #[test]
+    pub fn test_battery_pulses_and_gaps_block() {
+        let kinds = [ParticleKind::Battery, ParticleKind::Wire, ParticleKind::Wire, ParticleKind::Switch, ParticleKind::Wire];
+        let conducts = [false, true, true, false, true];
+        let mut charges = [Charge::None; 5];
+        let mut pulses = 0;
+        for _ in 0..9 {
+            propagate(&kinds, &conducts, &mut charges, 5);
+            if charges[2] == Charge::Head {
+                pulses += 1;
+            }
+            assert_eq!(charges[4], Charge::None);
+        }
+        assert_eq!(pulses, 3);
+    }
+
+}
@@ -5,34 +5,149 @@ use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
 use super::colour::*;
-use super::kinds::ParticleKind;
+use super::blast::{self, Blast};
+use super::electricity::{self, Charge};
+use super::hash::StableHasher;
+use super::heat::{self, AMBIENT_TEMPERATURE};
+use super::conflict::{Bid, ConflictPolicy};
+use super::kinds::{ParticleKind, Phase};
+use super::gravity::{self, Gravity};
+use super::motion;
+use super::wind;
+use super::life::{self, Rule};
 use super::particles::{
-    particle::{Particle, Neighbours},
+    particle::{Particle, Neighbours, Context, NEIGHBOUR_OFFSETS},
+    acid::AcidParticle,
+    ash::AshParticle,
     background::Background,
+    battery::BatteryParticle,
+    brine::BrineParticle,
     salt::SaltParticle,
+    seed::SeedParticle,
+    snow::SnowParticle,
+    soil::SoilParticle,
+    source::{self, SourceParticle},
+    sand::SandParticle,
+    stone::StoneParticle,
+    switch::SwitchParticle,
+    tnt::TntParticle,
     concrete::ConcreteParticle,
+    drain::DrainParticle,
+    fire::FireParticle,
+    gas::GasParticle,
+    glass::GlassParticle,
+    gunpowder::GunpowderParticle,
+    heater::HeaterParticle,
+    ice::IceParticle,
+    lamp::LampParticle,
+    lava::LavaParticle,
+    life::LifeParticle,
+    oil::OilParticle,
+    plant::PlantParticle,
     water::WaterParticle,
-    cornucopia::CornucopiaParticle
+    wire::WireParticle,
+    wood::WoodParticle,
+    cornucopia::{self, CornucopiaParticle}
 };
 use super::actions::Action;
 use super::point::Point;
+use super::recording::{InputEvent, Recording};
+use super::rng::Rng;
+use super::stats::Stats;
+use super::time::now_ms;
 
 
 const CANVAS_SIZE: usize = 128;
 const MENU_HEIGHT: usize = 24;
-const BASE_BUFFER_SIZE: usize = CANVAS_SIZE * CANVAS_SIZE;
-const GAME_N_ROWS: usize = CANVAS_SIZE - MENU_HEIGHT;
-const GAME_N_COLS: usize = CANVAS_SIZE;
-const GAME_N_CELLS: usize = GAME_N_COLS * GAME_N_ROWS;
-const OUTPUT_BUFFER_SIZE: usize = BASE_BUFFER_SIZE * 4;
+
+/// The kinds of particle offered in the menu, in the order they're laid out:
+/// down each column, then across.
+const PALETTE: [ParticleKind; 27] = [
+    ParticleKind::Background,
+    ParticleKind::Salt,
+    ParticleKind::Concrete,
+    ParticleKind::Water,
+    ParticleKind::Cornucopia,
+    ParticleKind::Sand,
+    ParticleKind::Wood,
+    ParticleKind::Fire,
+    ParticleKind::Ice,
+    ParticleKind::Lava,
+    ParticleKind::Stone,
+    ParticleKind::Oil,
+    ParticleKind::Acid,
+    ParticleKind::Glass,
+    ParticleKind::Snow,
+    ParticleKind::Gunpowder,
+    ParticleKind::Tnt,
+    ParticleKind::Soil,
+    ParticleKind::Seed,
+    ParticleKind::Wire,
+    ParticleKind::Battery,
+    ParticleKind::Heater,
+    ParticleKind::Lamp,
+    ParticleKind::Switch,
+    ParticleKind::Source,
+    ParticleKind::Drain,
+    ParticleKind::Life
+];
+
+/// Rows of swatches in the menu.
+const PALETTE_ROWS: usize = 3;
+
+/// Least and most distance between the columns of swatches in the menu. A
+/// swatch is 5 pixels wide.
+const MIN_SWATCH_SPACING: usize = 6;
+const MAX_SWATCH_SPACING: usize = 10;
+
+/// Smallest canvas with room for every swatch in the menu.
+const MIN_CANVAS_SIZE: usize = PALETTE.len().div_ceil(PALETTE_ROWS) * MIN_SWATCH_SPACING + 3;
+
+/// Largest canvas and longest run a recording may ask to be replayed with.
+const MAX_REPLAY_CANVAS_SIZE: usize = 1024;
+const MAX_REPLAY_TICKS: u32 = 100_000;
 
 /// A structure containing data and functions for running the game.
 #[wasm_bindgen]
 pub struct Game {
+    canvas_size: usize,
+    n_rows: usize,
+    n_cols: usize,
     cells: Vec<Rc<RefCell<dyn Particle>>>,
-    output_buffer: [u8; OUTPUT_BUFFER_SIZE],
+    temperature: Vec<f32>,
+    /// Velocity of the particle in each cell, in cells per tick, with
+    /// positive y downwards.
+    velocity: Vec<(f32, f32)>,
+    /// Velocity of the wind blowing through each cell, in cells per tick.
+    wind: Vec<(f32, f32)>,
+    charge: Vec<Charge>,
+    life_age: Vec<u8>,
+    life_rule: Rule,
+    output_buffer: Vec<u8>,
     current_brush: ParticleKind,
-    palette: HashMap<Point, ParticleKind>
+    source_kind: ParticleKind,
+    source_rate: f32,
+    cornucopia: cornucopia::Settings,
+    palette: HashMap<Point, ParticleKind>,
+    paused: bool,
+    /// Whether every particle decides what to do from the world as it was at
+    /// the start of the tick, rather than as left by those scanned before it.
+    synchronous: bool,
+    conflict_policy: ConflictPolicy,
+    gravity: Gravity,
+    /// Wind painted in place of particles, if the wind brush is in use.
+    wind_brush: Option<(f32, f32)>,
+    show_wind: bool,
+    ticks_per_frame: u32,
+    stats: Stats,
+    rng: Rng,
+    recording: Recording
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Functions for game
@@ -42,15 +157,55 @@ impl Game {
     /// create new game
     #[wasm_bindgen(constructor)]
     pub fn new() -> Game {
+        Game::with_canvas_size(CANVAS_SIZE)
+    }
+
+    /// create new game on a square canvas of side `canvas_size`, the bottom
+    /// `MENU_HEIGHT` rows of which are taken up by the menu. Canvases less
+    /// than `MIN_CANVAS_SIZE` across are widened to that, to fit the menu in.
+    pub fn with_canvas_size(canvas_size: usize) -> Game {
+        Game::with_seed(canvas_size, now_ms() as u64)
+    }
+
+    /// create new game as for `with_canvas_size`, with the random number
+    /// generator seeded by `seed`: games created with the same seed and given
+    /// the same inputs will always play out the same.
+    pub fn with_seed(canvas_size: usize, seed: u64) -> Game {
+        let canvas_size = canvas_size.max(MIN_CANVAS_SIZE);
+        let n_rows = canvas_size - MENU_HEIGHT;
+        let n_cols = canvas_size;
         let mut g = Game{
+            canvas_size,
+            n_rows,
+            n_cols,
             cells: Vec::new(),
-            output_buffer: [0; OUTPUT_BUFFER_SIZE],
+            temperature: vec![AMBIENT_TEMPERATURE; n_rows * n_cols],
+            velocity: vec![(0.0, 0.0); n_rows * n_cols],
+            wind: vec![(0.0, 0.0); n_rows * n_cols],
+            charge: vec![Charge::None; n_rows * n_cols],
+            life_age: vec![0; n_rows * n_cols],
+            life_rule: Rule::default(),
+            output_buffer: vec![0; canvas_size * canvas_size * 4],
             current_brush: ParticleKind::Salt,
-            palette: HashMap::new()
+            source_kind: source::DEFAULT_KIND,
+            source_rate: source::DEFAULT_RATE,
+            cornucopia: cornucopia::Settings::default(),
+            palette: HashMap::new(),
+            paused: false,
+            synchronous: false,
+            conflict_policy: ConflictPolicy::default(),
+            gravity: Gravity::default(),
+            wind_brush: None,
+            show_wind: false,
+            ticks_per_frame: 1,
+            stats: Stats::new(),
+            rng: Rng::new(seed),
+            recording: Recording::new(seed, canvas_size)
         };
 
-        for _ in 0..GAME_N_CELLS {
+        for _ in 0..(n_rows * n_cols) {
             g.cells.push(Rc::new(RefCell::new(Background)));
+            g.stats.added(ParticleKind::Background);
         }
 
         g.init_palette();
@@ -60,45 +215,32 @@ impl Game {
     }
 
     fn hline(&mut self, y: usize, colour: &Colour) {
-        for x in 0..GAME_N_COLS {
+        for x in 0..self.n_cols {
             self.putv(x, y, colour.as_uarr());
         }
     }
 
     fn init_canvas(&mut self) {
 
-        for y in GAME_N_ROWS..CANVAS_SIZE {
+        for y in self.n_rows..self.canvas_size {
             self.hline(y, &GRAY);
         }
 
-        let y = GAME_N_ROWS - 1;
-        for x in 0..GAME_N_COLS {
-            let idx = y*GAME_N_COLS + x;
-            self.cells[idx] = Game::get_cell_of_kind(ParticleKind::Concrete);
+        let y = self.n_rows - 1;
+        for x in 0..self.n_cols {
+            let idx = y*self.n_cols + x;
+            self.set_cell(idx, Game::get_cell_of_kind(ParticleKind::Concrete));
         }
     }
 
     fn init_palette(&mut self) {
-        let mut x: usize = 10;
-        let mut y: usize = GAME_N_ROWS + 5;
-
-        let kinds = vec!(
-            ParticleKind::Background,
-            ParticleKind::Salt,
-            ParticleKind::Concrete,
-            ParticleKind::Water,
-            ParticleKind::Cornucopia
-        );
-
-        for colour in kinds {
-            self.palette.insert(Point(x, y), colour);
-            y += 7;
-
-            if y >= CANVAS_SIZE {
-                y = GAME_N_ROWS + 5;
-                x += 10;
-            }
-
+        // spread the columns out as far as there's room for
+        let columns = PALETTE.len().div_ceil(PALETTE_ROWS);
+        let spacing = ((self.n_cols - 3) / columns).min(MAX_SWATCH_SPACING);
+        for (i, kind) in PALETTE.iter().enumerate() {
+            let x = spacing * (i / PALETTE_ROWS + 1);
+            let y = self.n_rows + 5 + 7 * (i % PALETTE_ROWS);
+            self.palette.insert(Point(x, y), *kind);
         }
     }
 
@@ -127,28 +269,379 @@ impl Game {
             ParticleKind::Salt => Rc::new(RefCell::new(SaltParticle::new())),
             ParticleKind::Concrete => Rc::new(RefCell::new(ConcreteParticle)),
             ParticleKind::Water => Rc::new(RefCell::new(WaterParticle::new())),
-            ParticleKind::Cornucopia => Rc::new(RefCell::new(CornucopiaParticle::new()))
+            ParticleKind::Cornucopia => Rc::new(RefCell::new(CornucopiaParticle::new())),
+            ParticleKind::Sand => Rc::new(RefCell::new(SandParticle::new())),
+            ParticleKind::Brine => Rc::new(RefCell::new(BrineParticle::new())),
+            ParticleKind::Wood => Rc::new(RefCell::new(WoodParticle)),
+            ParticleKind::Fire => Rc::new(RefCell::new(FireParticle::new())),
+            ParticleKind::Smoke => Rc::new(RefCell::new(GasParticle::smoke())),
+            ParticleKind::Steam => Rc::new(RefCell::new(GasParticle::steam())),
+            ParticleKind::Ash => Rc::new(RefCell::new(AshParticle::new())),
+            ParticleKind::Ice => Rc::new(RefCell::new(IceParticle::new())),
+            ParticleKind::Lava => Rc::new(RefCell::new(LavaParticle::new())),
+            ParticleKind::Stone => Rc::new(RefCell::new(StoneParticle)),
+            ParticleKind::Oil => Rc::new(RefCell::new(OilParticle::new())),
+            ParticleKind::Acid => Rc::new(RefCell::new(AcidParticle::new())),
+            ParticleKind::Glass => Rc::new(RefCell::new(GlassParticle)),
+            ParticleKind::Snow => Rc::new(RefCell::new(SnowParticle::new())),
+            ParticleKind::Gunpowder => Rc::new(RefCell::new(GunpowderParticle::new())),
+            ParticleKind::Tnt => Rc::new(RefCell::new(TntParticle::new())),
+            ParticleKind::Soil => Rc::new(RefCell::new(SoilParticle::new())),
+            ParticleKind::Seed => Rc::new(RefCell::new(SeedParticle::new())),
+            ParticleKind::Plant => Rc::new(RefCell::new(PlantParticle::new())),
+            ParticleKind::Wire => Rc::new(RefCell::new(WireParticle)),
+            ParticleKind::Battery => Rc::new(RefCell::new(BatteryParticle)),
+            ParticleKind::Heater => Rc::new(RefCell::new(HeaterParticle::new())),
+            ParticleKind::Lamp => Rc::new(RefCell::new(LampParticle::new())),
+            ParticleKind::Switch => Rc::new(RefCell::new(SwitchParticle::new())),
+            ParticleKind::Source => Rc::new(RefCell::new(SourceParticle::new())),
+            ParticleKind::Drain => Rc::new(RefCell::new(DrainParticle::new())),
+            ParticleKind::Life => Rc::new(RefCell::new(LifeParticle))
+        }
+    }
+
+    /// Replace the particle at `idx`, keeping the population counts up to
+    /// date. All changes to `cells` should go through here.
+    fn set_cell(&mut self, idx: usize, cell: Rc<RefCell<dyn Particle>>) {
+        let old = self.cells[idx].borrow().get_type();
+        let new = cell.borrow().get_type();
+        self.stats.replaced(old, new);
+        self.cells[idx] = cell;
+        self.velocity[idx] = (0.0, 0.0);
+        self.life_age[idx] = 0;
+    }
+
+    /// Index of the cell at (`x` + `dx`, `y` + `dy`), if that is inside the
+    /// game area.
+    fn offset_index(&self, x: i32, y: i32, dx: i32, dy: i32) -> Option<usize> {
+        let (nx, ny) = (x + dx, y + dy);
+        if nx < 0 || ny < 0 || nx as usize >= self.n_cols || ny as usize >= self.n_rows {
+            None
+        }
+        else {
+            Some(ny as usize * self.n_cols + nx as usize)
+        }
+    }
+
+    /// Replace the particle at `idx` with a new one of `kind`, unless it is
+    /// already of that kind.
+    fn become_kind(&mut self, idx: usize, kind: ParticleKind) {
+        if self.cells[idx].borrow().get_type() != kind {
+            let cell = Game::get_cell_of_kind(kind);
+            cell.borrow_mut().set_processed();
+            self.set_cell(idx, cell);
+        }
+    }
+
+    /// Replace the particle at `idx` with a freshly made one of `kind`, at
+    /// that kind's initial temperature.
+    fn spawn(&mut self, idx: usize, kind: ParticleKind) {
+        let cell = Game::get_cell_of_kind(kind);
+        cell.borrow_mut().set_processed();
+        self.set_cell(idx, cell);
+        self.temperature[idx] = kind.initial_temperature();
+    }
+
+    /// What the particle at (`x`, `y`) wants to do this tick, or `None` if it
+    /// has already had its turn.
+    fn decide(&mut self, x: i32, y: i32, heads: usize) -> Option<Action> {
+        let idx = y as usize * self.n_cols + x as usize;
+
+        // too hot or cold to stay as it is?
+        let kind = self.cells[idx].borrow().get_type();
+        if let Some(new_kind) = kind.transition(self.temperature[idx]) {
+            return Some(Action::Become(new_kind));
+        }
+
+        if self.cells[idx].borrow().get_was_processed() {
+            // this particle has already interacted this turn
+            return None;
+        }
+
+        // rules are written as if gravity pulled down, so show the particle
+        // the world turned round that way, and turn what it does back again
+        let gravity = self.gravity;
+        let neighbours = gravity.to_frame(&self.get_neighbours(x as usize, y as usize));
+        let powered = heads > 0 && self.powered(x, y);
+        let mut ctx = Context{rng: &mut self.rng, temperature: self.temperature[idx], powered};
+        let action = self.cells[idx].borrow_mut().get_action(neighbours, &mut ctx);
+        self.temperature[idx] = ctx.temperature;
+        let action = action.turned(|dx, dy| gravity.to_world(dx, dy));
+
+        // without gravity, nothing falls, flows or rises
+        let drifts = matches!(kind.phase(), Phase::Powder | Phase::Liquid | Phase::Gas);
+        let action = if gravity.is_zero() && drifts && matches!(action, Action::MoveInto{..} | Action::SwapWith{..}) {
+            Action::StayPut
+        } else {
+            action
+        };
+
+        // wind may carry it off instead, if it wasn't doing anything else
+        let w = self.wind[idx];
+        if w != (0.0, 0.0) && matches!(action, Action::StayPut | Action::MoveInto{..} | Action::SwapWith{..})
+            && self.rng.chance(wind::chance(kind, w)) {
+            let (dx, dy) = gravity::quantise(w.0, w.1);
+            let downwind = self.offset_index(x, y, dx, dy);
+            if downwind.is_some_and(|t| self.cells[t].borrow().get_type() == ParticleKind::Background) {
+                return Some(Action::MoveInto{x: dx, y: dy});
+            }
+        }
+        Some(action)
+    }
+
+    /// Carry out the `action` of the particle at (`x`, `y`). Blasts are
+    /// queued on `blasts` rather than set off straight away.
+    fn act(&mut self, x: i32, y: i32, action: Action, blasts: &mut Vec<Blast>) {
+        let idx = y as usize * self.n_cols + x as usize;
+        if !matches!(action, Action::MoveInto{..}) {
+            // anything that isn't flying along has come to a stop
+            self.velocity[idx] = (0.0, 0.0);
+        }
+        match action {
+            Action::Become(kind) => {
+                self.become_kind(idx, kind);
+                self.stats.becomes += 1;
+            },
+            Action::React{x: dx, y: dy, this, that} => {
+                if let Some(nidx) = self.offset_index(x, y, dx, dy) {
+                    self.spawn(nidx, that);
+                    self.become_kind(idx, this);
+                    self.stats.reactions += 1;
+                }
+            },
+            Action::Freeze{x: dx, y: dy} => {
+                if let Some(nidx) = self.offset_index(x, y, dx, dy) {
+                    self.become_kind(nidx, ParticleKind::Ice);
+                    self.temperature[nidx] = self.temperature[nidx].min(0.0);
+                    self.stats.reactions += 1;
+                }
+            },
+            Action::MoveInto{x: dx, y: dy} => {
+                let dest = match self.get_kind(x as usize, y as usize).phase() {
+                    Phase::Powder | Phase::Liquid => self.fly(x, y, dx, dy),
+                    _ => self.offset_index(x, y, dx, dy)
+                };
+                // the edge of the world is as solid as a wall
+                let Some(dest) = dest else { return };
+                if !self.move_to(idx, dest) {
+                    self.stats.overwrites += 1;
+                    return;
+                }
+                self.stats.moves += 1;
+            },
+            Action::SwapWith{x: dx, y: dy} => {
+                if let Some(nidx) = self.offset_index(x, y, dx, dy) {
+                    self.cells[idx].borrow_mut().set_processed();
+                    self.cells.swap(idx, nidx);
+                    self.temperature.swap(idx, nidx);
+                    self.velocity.swap(idx, nidx);
+                    self.stats.moves += 1;
+                }
+            },
+            Action::GrowInto{x: dx, y: dy, kind} => {
+                if let Some(nidx) = self.offset_index(x, y, dx, dy) {
+                    self.spawn(nidx, kind);
+                    self.stats.grows += 1;
+                }
+            },
+            Action::Divide{x: dx, y: dy} => {
+                let child = self.cells[idx].borrow().divide();
+                if let (Some(nidx), Some(child)) = (self.offset_index(x, y, dx, dy), child) {
+                    child.borrow_mut().set_processed();
+                    self.set_cell(nidx, child);
+                    self.temperature[nidx] = self.temperature[idx];
+                    self.stats.grows += 1;
+                }
+            },
+            Action::Pop => {
+                self.set_cell(idx, Game::get_cell_of_kind(ParticleKind::Background));
+                self.stats.pops += 1;
+            },
+            Action::Explode{radius} => {
+                blasts.push(Blast::new(x as usize, y as usize, radius));
+            },
+            Action::StayPut => return
+        }
+        self.stats.active += 1;
+    }
+
+    /// Put `cell`, the particle which was at (`x`, `y`), back the way it was
+    /// in `before`, along with its temperature, after what it decided to do
+    /// was called off. It may have been swapped into a neighbouring cell
+    /// since, and if it has been destroyed there's nothing to put back.
+    fn restore(&mut self, x: i32, y: i32, cell: &Rc<RefCell<dyn Particle>>, before: (Rc<RefCell<dyn Particle>>, f32)) {
+        let at = std::iter::once((0, 0)).chain(NEIGHBOUR_OFFSETS)
+            .filter_map(|(dx, dy)| self.offset_index(x, y, dx, dy))
+            .find(|&at| Rc::ptr_eq(&self.cells[at], cell));
+        if let Some(at) = at {
+            let (saved, temperature) = before;
+            saved.borrow_mut().set_processed();
+            self.cells[at] = saved;
+            self.temperature[at] = temperature;
+        }
+    }
+
+    /// Run a generation of the life rule on the cells of life.
+    fn live(&mut self, kinds: &[ParticleKind]) {
+        let changes = life::step(&self.life_rule, ParticleKind::Life, kinds, &mut self.life_age, self.n_cols);
+        for (idx, change) in changes {
+            match change {
+                life::Change::Born => {
+                    self.spawn(idx, ParticleKind::Life);
+                    self.stats.grows += 1;
+                },
+                life::Change::Died => {
+                    self.set_cell(idx, Game::get_cell_of_kind(ParticleKind::Background));
+                    self.stats.pops += 1;
+                }
+            }
+        }
+    }
+
+    /// Whether any of the neighbours of (`x`, `y`) carries the head of a
+    /// pulse.
+    fn powered(&self, x: i32, y: i32) -> bool {
+        NEIGHBOUR_OFFSETS.iter().any(|&(dx, dy)| {
+            self.offset_index(x, y, dx, dy).is_some_and(|n| self.charge[n] == Charge::Head)
+        })
+    }
+
+    /// Set off a blast: the cells around it are heated, and destroyed, set
+    /// alight or flung outwards depending on how hard they are and how close
+    /// they are to the centre.
+    fn detonate(&mut self, b: &Blast) {
+        self.spawn(b.y * self.n_cols + b.x, ParticleKind::Fire);
+        for (x, y, power) in b.cells(self.n_cols, self.n_rows) {
+            let idx = y * self.n_cols + x;
+            self.temperature[idx] += blast::BLAST_HEAT * power;
+            let kind = self.cells[idx].borrow().get_type();
+            let movable = matches!(kind.phase(), Phase::Powder | Phase::Liquid | Phase::Gas);
+
+            if kind == ParticleKind::Background || kind.hardness() < power / 2.0 {
+                if self.rng.chance(blast::FIRE_CHANCE) {
+                    self.spawn(idx, ParticleKind::Fire);
+                }
+                else if kind != ParticleKind::Background {
+                    self.set_cell(idx, Game::get_cell_of_kind(ParticleKind::Background));
+                }
+            }
+            else if movable && kind.hardness() < power {
+                // fling it as far away from the centre as it can get
+                let (dx, dy) = (x as f32 - b.x as f32, y as f32 - b.y as f32);
+                let d = (dx * dx + dy * dy).sqrt();
+                let mut dest = None;
+                for step in 1..=(power * b.radius as f32).ceil() as i32 {
+                    let tx = (dx / d * step as f32).round() as i32;
+                    let ty = (dy / d * step as f32).round() as i32;
+                    match self.offset_index(x as i32, y as i32, tx, ty) {
+                        Some(t) if self.cells[t].borrow().get_type() == ParticleKind::Background => dest = Some(t),
+                        _ => break
+                    }
+                }
+                if let Some(dest) = dest {
+                    self.move_to(idx, dest);
+                }
+            }
+        }
+    }
+
+    /// Where a falling or flowing particle at (`x`, `y`) which chose to step
+    /// by (`dx`, `dy`) ends up, carried along by its velocity until it runs
+    /// into something. Landing hard makes it bounce or splash, shoving aside
+    /// whatever it landed on if that can move.
+    fn fly(&mut self, x: i32, y: i32, dx: i32, dy: i32) -> Option<usize> {
+        let idx = y as usize * self.n_cols + x as usize;
+        let gravity = self.gravity;
+        let (gx, gy) = gravity.direction;
+        // how far the step goes with and across the pull of gravity
+        let falling = |dx: i32, dy: i32| dx*gx + dy*gy;
+        let across = |dx: i32, dy: i32| dx*gy - dy*gx;
+        let (vx, mut vy) = gravity.velocity_to_frame(self.velocity[idx]);
+        let empty = |t: Option<usize>| t.is_some_and(|t| self.cells[t].borrow().get_type() == ParticleKind::Background);
+        // anything sliding along fast keeps going the way it was going
+        let (dx, dy) = if falling(dx, dy) == 0 && vx.abs() >= 1.0 && (vx > 0.0) != (across(dx, dy) > 0)
+            && empty(self.offset_index(x, y, -dx, -dy)) { (-dx, -dy) } else { (dx, dy) };
+        // nothing steps off the edge of the world: it stops dead against it
+        let Some(mut dest) = self.offset_index(x, y, dx, dy) else {
+            self.velocity[idx] = (0.0, 0.0);
+            return None;
+        };
+        let falls = falling(dx, dy) > 0;
+        vy = if falls { (vy + motion::GRAVITY * gravity.strength).min(motion::TERMINAL_VELOCITY) } else { 0.0 };
+
+        // what it runs into, if anything: another cell, or (`None`) the edge
+        // of the world, which is as hard as a wall
+        let mut hit: Option<Option<usize>> = None;
+        let mut end = (dx, dy);
+        for (ox, oy) in motion::ray(motion::reach((dx, dy), gravity.velocity_to_world((vx, vy)))) {
+            match self.offset_index(x, y, ox, oy) {
+                Some(t) if self.cells[t].borrow().get_type() == ParticleKind::Background => {
+                    dest = t;
+                    end = (ox, oy);
+                },
+                t => {
+                    hit = Some(t);
+                    break;
+                }
+            }
         }
+        if hit.is_none() && falls {
+            hit = match self.offset_index(x, y, end.0 + gx, end.1 + gy) {
+                Some(t) if self.cells[t].borrow().get_type() == ParticleKind::Background => None,
+                t => Some(t)
+            };
+        }
+
+        let v = match hit {
+            Some(h) if falls => {
+                let kind = self.cells[idx].borrow().get_type();
+                let other = h.map(|h| self.cells[h].borrow().get_type());
+                let movable = other.filter(|o| matches!(o.phase(), Phase::Powder | Phase::Liquid));
+                // only a hard landing needs a side to bounce or splash off to
+                let sideways = if vx != 0.0 || vy < motion::IMPACT_SPEED { vx.signum() }
+                    else if self.rng.chance(0.5) { 1.0 } else { -1.0 };
+                let (v, shoved) = motion::impact((vx, vy), kind.density(), movable.map(|o| o.density()),
+                    kind.phase() == Phase::Liquid, sideways);
+                if let (Some(h), Some(shoved)) = (h, shoved) {
+                    self.velocity[h] = gravity.velocity_to_world(shoved);
+                }
+                v
+            },
+            // turning round loses any speed it had the other way
+            _ if across(dx, dy) != 0 && (vx > 0.0) != (across(dx, dy) > 0) => (0.0, vy),
+            _ => (vx * motion::DRAG, vy)
+        };
+        self.velocity[idx] = gravity.velocity_to_world(v);
+        Some(dest)
     }
 
-    fn move_to(&mut self, source_idx: usize, dest_idx: usize) {
-        if dest_idx < GAME_N_CELLS {
-            self.cells[dest_idx] = self.cells[source_idx].clone();
-            self.cells[dest_idx].borrow_mut().set_processed();
+    /// Move the particle at `source_idx` into the empty cell at `dest_idx`.
+    /// Returns false, leaving both cells alone, if the destination isn't
+    /// empty.
+    fn move_to(&mut self, source_idx: usize, dest_idx: usize) -> bool {
+        if self.cells[dest_idx].borrow().get_type() != ParticleKind::Background {
+            return false;
         }
-        // else { /* out-of-bounds */ }
+        let cell = self.cells[source_idx].clone();
+        cell.borrow_mut().set_processed();
+        self.set_cell(dest_idx, cell);
+        // the particle takes its heat with it, and whatever it displaced
+        // (air, probably) takes the place it left
+        self.temperature.swap(source_idx, dest_idx);
+        self.velocity.swap(source_idx, dest_idx);
 
-        self.cells[source_idx] = Game::get_cell_of_kind(ParticleKind::Background);
+        self.set_cell(source_idx, Game::get_cell_of_kind(ParticleKind::Background));
+        true
     }
 
     #[wasm_bindgen]
     pub fn get_canvas_size(&self) -> i32 {
-        CANVAS_SIZE as i32
+        self.canvas_size as i32
     }
 
     #[wasm_bindgen]
     pub fn clicked(&mut self, x: usize, y: usize) {
-        if y >= GAME_N_ROWS {
+        if y >= self.n_rows {
             self.menu_clicked(x, y)
         }
         else {
@@ -157,8 +650,37 @@ impl Game {
     }
 
     fn paint(&mut self, x: usize, y: usize) {
-        let idx = y*GAME_N_COLS + x;
-        self.cells[idx] = Game::get_cell_of_kind(self.current_brush);
+        if x >= self.n_cols || y >= self.n_rows {
+            return;
+        }
+        self.record(InputEvent::Paint{x, y});
+        if let Some(v) = self.wind_brush {
+            wind::paint(&mut self.wind, self.n_cols, x as i32, y as i32, v);
+            return;
+        }
+        let idx = y*self.n_cols + x;
+        self.set_cell(idx, self.brush_cell());
+        self.temperature[idx] = self.current_brush.initial_temperature();
+    }
+
+    /// A new particle of the current brush's kind, set up with the brush's
+    /// settings for that kind.
+    fn brush_cell(&self) -> Rc<RefCell<dyn Particle>> {
+        match self.current_brush {
+            ParticleKind::Source => Rc::new(RefCell::new(SourceParticle::with_settings(self.source_kind, self.source_rate))),
+            ParticleKind::Cornucopia => Rc::new(RefCell::new(CornucopiaParticle::with_settings(self.cornucopia))),
+            kind => Game::get_cell_of_kind(kind)
+        }
+    }
+
+    /// Flip the particle at (`x`, `y`) on or off, if it's something that can
+    /// be switched, like a switch.
+    #[wasm_bindgen]
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        if x < self.n_cols && y < self.n_rows {
+            self.record(InputEvent::Toggle{x, y});
+            self.cells[y*self.n_cols + x].borrow_mut().toggle();
+        }
     }
 
     fn menu_clicked(&mut self, x: usize, y: usize) {
@@ -166,15 +688,146 @@ impl Game {
             for dy in 0..5 {
                 let pt = Point(x + dx - 2, y + dy - 2);
                 if let Some(c) = self.palette.get(&pt) {
-                    self.current_brush = c.clone();
-                    self.init_menu();
-                    self.draw_selection(pt.0, pt.1);
+                    self.set_brush(*c);
                     return;
                 }
             }
         }
     }
 
+    /// Select the kind of particle painted by clicking in the game area.
+    #[wasm_bindgen]
+    pub fn set_brush(&mut self, kind: ParticleKind) {
+        self.record(InputEvent::SetBrush(kind));
+        self.current_brush = kind;
+        self.wind_brush = None;
+        self.init_menu();
+        let selected = self.palette.iter().find(|(_, k)| **k == kind).map(|(pt, _)| pt.clone());
+        if let Some(pt) = selected {
+            self.draw_selection(pt.0, pt.1);
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn get_brush(&self) -> ParticleKind {
+        self.current_brush
+    }
+
+    /// Set what sources painted from now on emit, and the chance per tick
+    /// that they emit a particle. Sources already painted keep their
+    /// settings.
+    #[wasm_bindgen]
+    pub fn set_source(&mut self, kind: ParticleKind, rate: f32) {
+        self.record(InputEvent::SetSource{kind, rate});
+        self.source_kind = kind;
+        self.source_rate = rate.clamp(0.0, 1.0);
+    }
+
+    pub fn get_source_kind(&self) -> ParticleKind {
+        self.source_kind
+    }
+
+    pub fn get_source_rate(&self) -> f32 {
+        self.source_rate
+    }
+
+    /// Set the rule cells of life live by, as a rule string like "B3/S23".
+    #[wasm_bindgen]
+    pub fn set_life_rule(&mut self, rule: &str) -> Result<(), String> {
+        let rule: Rule = rule.parse()?;
+        self.record(InputEvent::SetLifeRule(rule.clone()));
+        self.life_rule = rule;
+        Ok(())
+    }
+
+    pub fn get_life_rule(&self) -> String {
+        self.life_rule.to_string()
+    }
+
+    /// Set how cornucopias painted from now on behave: what they emit
+    /// (`Background` to copy whatever touches them first), the chance per
+    /// tick that they emit a particle, which neighbour (`dx`, `dy`) they
+    /// emit into by preference ((0, 0) for none), and what kind of particle
+    /// makes them forget what they've copied (`Background` for none).
+    #[wasm_bindgen]
+    pub fn set_cornucopia(&mut self, kind: ParticleKind, rate: f32, dx: i32, dy: i32, reset_by: ParticleKind) {
+        let settings = cornucopia::Settings::new(kind, rate, (dx, dy), reset_by);
+        self.record(InputEvent::SetCornucopia(settings));
+        self.cornucopia = settings;
+    }
+
+    /// Serialised log of every input to the game so far, which can be passed
+    /// to `replay` to reproduce the current state of the world.
+    #[wasm_bindgen]
+    pub fn get_recording(&mut self) -> String {
+        self.recording.end_tick = self.stats.tick;
+        self.recording.to_string()
+    }
+
+    /// Create a new game from a recording made by `get_recording`, replaying
+    /// all of its inputs at the ticks they were made. Recordings of a canvas
+    /// too small to play on, or with clicks outside the game area, are
+    /// rejected.
+    #[wasm_bindgen]
+    pub fn replay(recording: &str) -> Result<Game, String> {
+        let recording: Recording = recording.parse()?;
+        if recording.canvas_size < MIN_CANVAS_SIZE {
+            return Err(format!("canvas {} is too small", recording.canvas_size));
+        }
+        if recording.canvas_size > MAX_REPLAY_CANVAS_SIZE {
+            return Err(format!("canvas {} is too large", recording.canvas_size));
+        }
+        if recording.end_tick > MAX_REPLAY_TICKS {
+            return Err(format!("recording ends at tick {}, after the last tick allowed", recording.end_tick));
+        }
+        let mut g = Game::with_seed(recording.canvas_size, recording.seed);
+        for (tick, event) in recording.events.iter() {
+            if *tick > MAX_REPLAY_TICKS {
+                return Err(format!("event at tick {} is after the last tick allowed: {}", tick, event));
+            }
+            if let InputEvent::Paint{x, y} | InputEvent::Toggle{x, y} = *event {
+                if x >= g.n_cols || y >= g.n_rows {
+                    return Err(format!("event at tick {} is outside the game area: {}", tick, event));
+                }
+            }
+        }
+        for (tick, event) in recording.events {
+            while g.stats.tick < tick {
+                g.simulate();
+            }
+            g.apply(event);
+        }
+        while g.stats.tick < recording.end_tick {
+            g.simulate();
+        }
+        g.render();
+        Ok(g)
+    }
+
+    fn record(&mut self, event: InputEvent) {
+        self.recording.push(self.stats.tick, event);
+    }
+
+    fn apply(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::Paint{x, y} => self.paint(x, y),
+            InputEvent::SetBrush(kind) => self.set_brush(kind),
+            InputEvent::Toggle{x, y} => self.toggle(x, y),
+            InputEvent::SetSource{kind, rate} => self.set_source(kind, rate),
+            InputEvent::SetCornucopia(s) => self.set_cornucopia(s.kind, s.rate, s.direction.0, s.direction.1, s.reset_by),
+            InputEvent::SetLifeRule(rule) => {
+                self.record(InputEvent::SetLifeRule(rule.clone()));
+                self.life_rule = rule;
+            },
+            InputEvent::SetSynchronous(on) => self.set_synchronous(on),
+            InputEvent::SetConflictPolicy(policy) => self.set_conflict_policy(policy),
+            InputEvent::SetGravity(g) => self.set_gravity(g.direction.0 as f32, g.direction.1 as f32, g.strength),
+            InputEvent::SetWindBrush{x, y} => self.set_wind_brush(x, y),
+            InputEvent::SetWind{x, y} => self.set_uniform_wind(x, y),
+            InputEvent::AddVortex{x, y, radius, strength} => self.add_vortex(x, y, radius, strength)
+        }
+    }
+
     fn draw_selection(&mut self, x: usize, y: usize) {
         for dx in [0, 4] {
             for dy in 0..5 {
@@ -188,116 +841,405 @@ impl Game {
         }
     }
 
+    /// Advance the simulation by `ticks_per_frame` ticks, unless paused, then
+    /// redraw the output buffer.
     #[wasm_bindgen]
     pub fn update(&mut self) {
-
-        for y in 0..GAME_N_ROWS {
-            for x in 0..GAME_N_COLS {
-                let idx = y*CANVAS_SIZE + x;
-                self.cells[idx].borrow_mut().tick();
+        if !self.paused {
+            for _ in 0..self.ticks_per_frame {
+                self.simulate();
             }
         }
+        self.render();
+    }
 
-        for y in 0..(GAME_N_ROWS as i32) {
-            for x in 0..(GAME_N_COLS as i32) {
-                let idx = y as usize * GAME_N_COLS + x as usize;
-                if self.cells[idx].borrow().get_was_processed() {
-                    // this particle has already interacted this turn, 
-                    continue;
-                }
+    /// Advance the simulation by exactly `n` ticks, paused or not, then
+    /// redraw the output buffer.
+    #[wasm_bindgen]
+    pub fn step(&mut self, n: u32) {
+        for _ in 0..n {
+            self.simulate();
+        }
+        self.render();
+    }
 
-                let mut neighbours: Neighbours = [[ParticleKind::Background; 3]; 3];
-                for dy in 0..3 {
-                    let ny = y + dy - 1;
-                    if (ny < 0) || (ny as usize >= GAME_N_ROWS) {
-                        continue;
-                    }
-                    for dx in 0..3 {
-                        let nx = x + dx - 1;
-                        if (nx < 0) || (nx as usize >= GAME_N_COLS) {
-                            continue;
-                        }
-                        let nidx = ny as usize * GAME_N_COLS + nx as usize;
-                        if nidx >= GAME_N_CELLS {
-                            continue;
-                        }
-                        else {
-                            neighbours[dy as usize][dx as usize] = self.cells[nidx].borrow().get_type();
-                        }
+    #[wasm_bindgen]
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    #[wasm_bindgen]
+    pub fn toggle_paused(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    #[wasm_bindgen]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Switch between updating the world in place, cell by cell, and the
+    /// synchronous mode in which every particle acts on the previous frame
+    /// and clashing actions are dropped.
+    #[wasm_bindgen]
+    pub fn set_synchronous(&mut self, synchronous: bool) {
+        self.record(InputEvent::SetSynchronous(synchronous));
+        self.synchronous = synchronous;
+    }
+
+    #[wasm_bindgen]
+    pub fn is_synchronous(&self) -> bool {
+        self.synchronous
+    }
+
+    /// Set how synchronous mode chooses between particles which want to
+    /// change the same cell.
+    #[wasm_bindgen]
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) {
+        self.record(InputEvent::SetConflictPolicy(policy));
+        self.conflict_policy = policy;
+    }
+
+    #[wasm_bindgen]
+    pub fn get_conflict_policy(&self) -> ConflictPolicy {
+        self.conflict_policy
+    }
+
+    /// Make gravity pull along (`x`, `y`), to the nearest of the eight
+    /// directions around a cell, `strength` times as hard as normal. A zero
+    /// vector or strength switches gravity off.
+    #[wasm_bindgen]
+    pub fn set_gravity(&mut self, x: f32, y: f32, strength: f32) {
+        let gravity = Gravity::new(x, y, strength);
+        self.record(InputEvent::SetGravity(gravity));
+        self.gravity = gravity;
+    }
+
+    #[wasm_bindgen]
+    pub fn get_gravity_x(&self) -> i32 {
+        self.gravity.direction.0
+    }
+
+    #[wasm_bindgen]
+    pub fn get_gravity_y(&self) -> i32 {
+        self.gravity.direction.1
+    }
+
+    #[wasm_bindgen]
+    pub fn get_gravity_strength(&self) -> f32 {
+        self.gravity.strength
+    }
+
+    /// Paint wind blowing at (`x`, `y`) cells per tick, rather than
+    /// particles, until another kind of particle is chosen with `set_brush`.
+    #[wasm_bindgen]
+    pub fn set_wind_brush(&mut self, x: f32, y: f32) {
+        self.record(InputEvent::SetWindBrush{x, y});
+        self.wind_brush = Some((x, y));
+    }
+
+    #[wasm_bindgen]
+    pub fn is_painting_wind(&self) -> bool {
+        self.wind_brush.is_some()
+    }
+
+    /// Make the wind blow at (`x`, `y`) cells per tick everywhere, replacing
+    /// any wind there was. Zero stills the air.
+    #[wasm_bindgen]
+    pub fn set_uniform_wind(&mut self, x: f32, y: f32) {
+        self.record(InputEvent::SetWind{x, y});
+        wind::uniform(&mut self.wind, (x, y));
+    }
+
+    /// Add a whirlwind centred on (`x`, `y`). See `wind::vortex`.
+    #[wasm_bindgen]
+    pub fn add_vortex(&mut self, x: i32, y: i32, radius: i32, strength: f32) {
+        self.record(InputEvent::AddVortex{x, y, radius, strength});
+        wind::vortex(&mut self.wind, self.n_cols, x, y, radius, strength);
+    }
+
+    /// Tint each cell by the wind blowing through it when rendering.
+    #[wasm_bindgen]
+    pub fn set_show_wind(&mut self, show: bool) {
+        self.show_wind = show;
+    }
+
+    #[wasm_bindgen]
+    pub fn is_showing_wind(&self) -> bool {
+        self.show_wind
+    }
+
+    /// Set how many simulation ticks are run per call to `update`. Zero is
+    /// equivalent to pausing.
+    #[wasm_bindgen]
+    pub fn set_ticks_per_frame(&mut self, n: u32) {
+        self.ticks_per_frame = n;
+    }
+
+    #[wasm_bindgen]
+    pub fn get_ticks_per_frame(&self) -> u32 {
+        self.ticks_per_frame
+    }
+
+    /// Population counts and a summary of what happened in the last tick.
+    #[wasm_bindgen]
+    pub fn get_stats(&self) -> Stats {
+        self.stats.clone()
+    }
+
+    /// Hash of the kind and state of every particle in the world. Two games
+    /// with equal hashes are (almost certainly) in the same state.
+    #[wasm_bindgen]
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        hasher.write_u64(self.n_cols as u64);
+        hasher.write_u64(self.n_rows as u64);
+        for idx in 0..self.cells.len() {
+            self.hash_cell(idx, &mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Hashes of each `chunk_size` by `chunk_size` square of the world, in
+    /// row-major order, so that a difference between two worlds can be
+    /// narrowed down to a region. Chunks on the right and bottom edges may be
+    /// cut short. There are no hashes of empty chunks, so a `chunk_size` of
+    /// zero gives none.
+    #[wasm_bindgen]
+    pub fn chunk_hashes(&self, chunk_size: usize) -> Vec<u64> {
+        let mut hashes = Vec::new();
+        if chunk_size == 0 {
+            return hashes;
+        }
+        for cy in (0..self.n_rows).step_by(chunk_size) {
+            for cx in (0..self.n_cols).step_by(chunk_size) {
+                let mut hasher = StableHasher::new();
+                for y in cy..(cy + chunk_size).min(self.n_rows) {
+                    for x in cx..(cx + chunk_size).min(self.n_cols) {
+                        self.hash_cell(y*self.n_cols + x, &mut hasher);
                     }
                 }
+                hashes.push(hasher.finish());
+            }
+        }
+        hashes
+    }
+
+    fn hash_cell(&self, idx: usize, hasher: &mut StableHasher) {
+        let cell = self.cells[idx].borrow();
+        hasher.write_u8(cell.get_type() as u8);
+        hasher.write_u64(cell.get_state());
+        hasher.write_u64(self.temperature[idx].to_bits() as u64);
+        let (vx, vy) = self.velocity[idx];
+        hasher.write_u64(((vx.to_bits() as u64) << 32) | vy.to_bits() as u64);
+        let (wx, wy) = self.wind[idx];
+        hasher.write_u64(((wx.to_bits() as u64) << 32) | wy.to_bits() as u64);
+        hasher.write_u8(self.charge[idx] as u8);
+        hasher.write_u8(self.life_age[idx]);
+    }
+
+    /// Write the colour of every cell in the game area into the output buffer.
+    #[wasm_bindgen]
+    pub fn render(&mut self) {
+        for idx in 0..self.cells.len() {
+            let colour = match self.charge[idx] {
+                Charge::Head if self.cells[idx].borrow().conducts() => ELECTRON_HEAD,
+                Charge::Tail if self.cells[idx].borrow().conducts() => ELECTRON_TAIL,
+                _ if self.life_age[idx] > 0 => LIFE_DYING,
+                _ => self.cells[idx].borrow().get_colour()
+            };
+            let colour = if self.show_wind { wind::overlay(&colour, self.wind[idx]) } else { colour };
+            self.output_buffer[idx*4..idx*4 + 4].copy_from_slice(&colour.as_uarr());
+        }
+    }
+
+    /// Run a single tick of the simulation: every particle gets a chance to
+    /// act once.
+    fn simulate(&mut self) {
+        let start = now_ms();
+        self.stats.begin_tick();
+
+        for cell in self.cells.iter() {
+            cell.borrow_mut().tick();
+        }
+
+        let kinds: Vec<ParticleKind> = self.cells.iter().map(|c| c.borrow().get_type()).collect();
+        heat::conduct(&kinds, &mut self.temperature, self.n_cols);
+        let conducts: Vec<bool> = self.cells.iter().map(|c| c.borrow().conducts()).collect();
+        let heads = electricity::propagate(&kinds, &conducts, &mut self.charge, self.n_cols);
+        if self.stats.count(ParticleKind::Life) > 0 {
+            self.live(&kinds);
+        }
+
+        let mut blasts = Vec::new();
 
-                let action = self.cells[idx].borrow_mut().get_action(neighbours);
-                match action {
-                    Action::Become(_kind) => (/* TODO */),
-                    Action::MoveInto{x: dx, y: dy} => {
-                        self.move_to(idx, (y + dy) as usize * GAME_N_COLS + (x + dx) as usize);
-                    },
-                    Action::GrowInto{x: dx, y: dy, kind} => {
-                        self.cells[(y + dy) as usize * GAME_N_COLS + (x + dx) as usize] = Game::get_cell_of_kind(kind);
-                    },
-                    Action::Pop => self.cells[idx] = Game::get_cell_of_kind(ParticleKind::Background),
-                    Action::StayPut => ()
+        if self.synchronous {
+            // decide everything from the world as it was at the start of the
+            // pass, then carry out whatever doesn't clash
+            let mut actions = Vec::new();
+            // how each particle was before deciding, in case it's stopped
+            let mut before = Vec::new();
+            for y in 0..(self.n_rows as i32) {
+                for x in 0..(self.n_cols as i32) {
+                    let idx = y as usize * self.n_cols + x as usize;
+                    let cell = Rc::clone(&self.cells[idx]);
+                    let snapshot = (cell.borrow().snapshot(), self.temperature[idx]);
+                    if let Some(action) = self.decide(x, y, heads) {
+                        actions.push((x, y, action));
+                        before.push((cell, snapshot));
+                    }
+                }
+            }
+            let bids: Vec<Bid> = actions.iter().map(|(x, y, action)| Bid{
+                kind: self.get_kind(*x as usize, *y as usize),
+                offset: match action {
+                    Action::MoveInto{x, y} | Action::SwapWith{x, y} => (*x, *y),
+                    _ => (0, 0)
+                },
+                velocity: self.velocity[*y as usize * self.n_cols + *x as usize]
+            }).collect();
+            let order = self.conflict_policy.order(&bids, &mut self.rng);
+            let mut actions: Vec<Option<(i32, i32, Action)>> = actions.into_iter().map(Some).collect();
+            let mut claimed = vec![false; self.cells.len()];
+            for i in order {
+                let Some((x, y, action)) = actions[i].take() else { continue };
+                if let Action::StayPut | Action::Explode{..} = action {
+                    self.act(x, y, action, &mut blasts);
+                    continue;
+                }
+                let idx = y as usize * self.n_cols + x as usize;
+                let target = action.target().and_then(|(dx, dy)| self.offset_index(x, y, dx, dy));
+                if claimed[idx] || target.is_some_and(|t| claimed[t]) {
+                    self.stats.conflicts += 1;
+                    let (cell, snapshot) = &before[i];
+                    self.restore(x, y, cell, snapshot.clone());
+                    continue;
                 }
+                claimed[idx] = true;
+                if let Some(t) = target {
+                    claimed[t] = true;
+                }
+                self.act(x, y, action, &mut blasts);
             }
         }
-
-        for y in 0..GAME_N_ROWS {
-            for x in 0..GAME_N_COLS {
-                let idx = y*GAME_N_COLS + x;
-                let colour = self.cells[idx].borrow().get_colour();
-                let colour_vec = colour.as_uarr();
-                for off in 0..3 {
-                    self.output_buffer[idx*4 + off] = colour_vec[off];
+        else {
+            for y in 0..(self.n_rows as i32) {
+                for x in 0..(self.n_cols as i32) {
+                    if let Some(action) = self.decide(x, y, heads) {
+                        self.act(x, y, action, &mut blasts);
+                    }
                 }
-                self.output_buffer[idx*4 + 3] = colour_vec[3];
             }
         }
-        
 
+        for b in blasts {
+            self.detonate(&b);
+            self.stats.explosions += 1;
+        }
+
+        self.stats.tick += 1;
+        self.stats.tick_duration_ms = now_ms() - start;
     }
 
     pub fn get_output_buffer_pointer(&self) -> *const u8 {
-        let pointer: *const u8;
-        pointer = self.output_buffer.as_ptr();
-        return pointer;
+        self.output_buffer.as_ptr()
     }
     
     fn putv(&mut self, x: usize, y: usize, v: [u8; 4]) {
-        let idx = (y*GAME_N_COLS + x)*4;
-        for off in 0..4 {
-            self.output_buffer[idx + off] = v[off];
-        }
-    }
-    
-    fn getv(&self, x: usize, y: usize) -> [u8; 4] {
-        let mut rv: [u8; 4] = [0; 4];
-        let idx = (y*GAME_N_COLS + x)*4;
-        for off in 0..4 {
-            rv[off] = self.output_buffer[idx + off];
-        }
-        rv
-    }
-    
-    fn getiv(&self, x: usize, y: usize) -> i32 {
-        let mut iarr: [i32; 4] = [0; 4];
-        let idx = (y*GAME_N_COLS + x)*4;
-        for i in 0..4 {
-            iarr[i] = self.output_buffer[idx + i] as i32;
-        }
-        (iarr[0] << 16) + (iarr[1] << 8) + iarr[2]
+        let idx = (y*self.n_cols + x)*4;
+        self.output_buffer[idx..idx + 4].copy_from_slice(&v);
     }
 }
 
+/// Functions for game which are not exported to javascript.
+impl Game {
 
-#[allow(dead_code,unused_imports)]
-mod tests {
+    /// Number of rows of cells in the game area.
+    pub fn get_n_rows(&self) -> usize {
+        self.n_rows
+    }
 
-    use super::*;
+    /// Number of columns of cells in the game area.
+    pub fn get_n_cols(&self) -> usize {
+        self.n_cols
+    }
 
-    #[test]
-    pub fn test_init() {
-        let _ = Game::new();
+    /// Place a particle of `kind` at (`x`, `y`), regardless of brush. This is
+    /// not an input from the player, so is not recorded.
+    pub fn put_particle(&mut self, x: usize, y: usize, kind: ParticleKind) {
+        let idx = y*self.n_cols + x;
+        self.set_cell(idx, Game::get_cell_of_kind(kind));
+        self.temperature[idx] = kind.initial_temperature();
+    }
+
+    /// Temperature of the cell at (`x`, `y`), in degrees C.
+    pub fn get_temperature(&self, x: usize, y: usize) -> f32 {
+        self.temperature[y*self.n_cols + x]
+    }
+
+    /// Heat or cool the cell at (`x`, `y`) to `temperature` degrees C.
+    pub fn set_temperature(&mut self, x: usize, y: usize, temperature: f32) {
+        self.temperature[y*self.n_cols + x] = temperature;
+    }
+
+    /// Kind of the particle at (`x`, `y`).
+    pub fn get_kind(&self, x: usize, y: usize) -> ParticleKind {
+        self.cells[y*self.n_cols + x].borrow().get_type()
+    }
+
+    /// Gather the kinds of the 3x3 block of cells centred on (`x`, `y`).
+    /// Cells outside of the game area are reported as background.
+    pub fn get_neighbours(&self, x: usize, y: usize) -> Neighbours {
+        let mut neighbours: Neighbours = [[ParticleKind::Background; 3]; 3];
+        let (x, y) = (x as i32, y as i32);
+        for (dy, row) in neighbours.iter_mut().enumerate() {
+            let ny = y + dy as i32 - 1;
+            if (ny < 0) || (ny as usize >= self.n_rows) {
+                continue;
+            }
+            for (dx, kind) in row.iter_mut().enumerate() {
+                let nx = x + dx as i32 - 1;
+                if (nx < 0) || (nx as usize >= self.n_cols) {
+                    continue;
+                }
+                let nidx = ny as usize * self.n_cols + nx as usize;
+                *kind = self.cells[nidx].borrow().get_type();
+            }
+        }
+        neighbours
+    }
+
+    /// Pixel value at (`x`, `y`) in the output buffer.
+    pub fn getv(&self, x: usize, y: usize) -> [u8; 4] {
+        let mut rv: [u8; 4] = [0; 4];
+        let idx = (y*self.n_cols + x)*4;
+        rv.copy_from_slice(&self.output_buffer[idx..idx + 4]);
+        rv
+    }
+
+    /// Pixel value at (`x`, `y`) in the output buffer, packed as by
+    /// `Colour::as_i`.
+    pub fn getiv(&self, x: usize, y: usize) -> i32 {
+        let v = self.getv(x, y);
+        ((v[0] as i32) << 16) + ((v[1] as i32) << 8) + v[2] as i32
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::particles::brine::SATURATION;
+    use crate::particles::acid::STRENGTH;
+    use crate::particles::particle::NEIGHBOUR_OFFSETS;
+    use crate::particles::heater;
+    use crate::kinds::METAL_MELT_TEMPERATURE;
+    use crate::particles::plant::Part;
+
+    #[test]
+    pub fn test_init() {
+        let _ = Game::new();
     }
 
     #[test]
@@ -308,4 +1250,1314 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_nothing_flows_off_the_sides() {
+        let mut g = Game::with_seed(64, 29);
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 8)..floor {
+            for x in [0, 1, 62, 63] {
+                g.put_particle(x, y, ParticleKind::Water);
+            }
+        }
+        for _ in 0..200 {
+            g.step(1);
+            assert_eq!(g.get_stats().count(ParticleKind::Water), 32);
+        }
+    }
+
+    #[test]
+    pub fn test_palette_fits_smallest_canvas() {
+        let mut g = Game::with_seed(MIN_CANVAS_SIZE, 26);
+        assert_eq!(g.palette.len(), PALETTE.len());
+        let mut swatches: Vec<&Point> = g.palette.keys().collect();
+        swatches.sort_by_key(|pt| (pt.1, pt.0));
+        for pt in swatches.iter() {
+            assert!(pt.0 >= 2 && pt.0 + 2 < g.n_cols);
+            assert!(pt.1 >= g.n_rows + 2 && pt.1 + 2 < g.canvas_size);
+        }
+        // no two swatches overlap, borders and all
+        for (a, b) in swatches.iter().zip(swatches.iter().skip(1)) {
+            assert!(a.1 != b.1 || b.0 - a.0 >= 5);
+        }
+        // and clicking each one picks it
+        for kind in PALETTE {
+            let (pt, _) = g.palette.iter().find(|(_, k)| **k == kind).unwrap();
+            let pt = pt.clone();
+            g.clicked(pt.0, pt.1);
+            assert_eq!(g.get_brush(), kind);
+        }
+        g.render();
+    }
+
+    #[test]
+    pub fn test_sizes() {
+        for size in [MIN_CANVAS_SIZE, 64, 256] {
+            let mut g = Game::with_canvas_size(size);
+            assert_eq!(g.get_n_rows(), size - MENU_HEIGHT);
+            assert_eq!(g.get_n_cols(), size);
+            g.put_particle(size/2, 0, ParticleKind::Sand);
+            for _ in 0..size {
+                g.update();
+            }
+            assert_eq!(g.get_kind(size/2, g.get_n_rows() - 2), ParticleKind::Sand);
+            assert!(SAND.i_eq(g.getiv(size/2, g.get_n_rows() - 2)));
+        }
+    }
+
+    #[test]
+    pub fn test_small_canvas_is_widened() {
+        for size in [0, 30, MIN_CANVAS_SIZE - 1] {
+            let mut g = Game::with_seed(size, 1);
+            assert_eq!(g.get_canvas_size() as usize, MIN_CANVAS_SIZE);
+            assert_eq!(g.palette.len(), PALETTE.len());
+            g.update();
+        }
+    }
+
+    #[test]
+    pub fn test_pause_and_step() {
+        let mut g = Game::new();
+        g.put_particle(10, 0, ParticleKind::Sand);
+        g.set_paused(true);
+        for _ in 0..5 {
+            g.update();
+        }
+        assert_eq!(g.get_kind(10, 0), ParticleKind::Sand);
+        assert!(SAND.i_eq(g.getiv(10, 0)));
+
+        g.step(3);
+        assert_eq!(g.get_kind(10, 3), ParticleKind::Sand);
+        assert!(BLACK.i_eq(g.getiv(10, 0)));
+        assert!(SAND.i_eq(g.getiv(10, 3)));
+
+        g.set_paused(false);
+        g.set_ticks_per_frame(4);
+        g.update();
+        // picking up speed as it goes: 2, 2, 3 and 3 cells
+        assert_eq!(g.get_kind(10, 13), ParticleKind::Sand);
+    }
+
+    /// Height of the pile in each column, counting up from the floor.
+    fn heights(g: &Game, kind: ParticleKind) -> Vec<usize> {
+        (0..g.get_n_cols()).map(|x| {
+            (0..g.get_n_rows()).filter(|y| g.get_kind(x, *y) == kind).count()
+        }).collect()
+    }
+
+    #[test]
+    pub fn test_sand_piles_into_cone() {
+        let mut g = Game::with_seed(64, 31);
+        let mut poured = 0;
+        for _ in 0..300 {
+            if g.get_kind(32, 0) == ParticleKind::Background {
+                g.put_particle(32, 0, ParticleKind::Sand);
+                poured += 1;
+            }
+            g.step(1);
+        }
+        g.step(200);
+        let h = heights(&g, ParticleKind::Sand);
+        assert_eq!(h.iter().sum::<usize>(), poured);
+        let peak = h[32];
+        assert_eq!(h.iter().max(), Some(&peak));
+        assert!(h.iter().filter(|c| **c > 0).count() > 10);
+        for x in 1..h.len() {
+            // at rest, neighbouring columns differ by no more than a grain
+            assert!(h[x].abs_diff(h[x - 1]) <= 1);
+        }
+    }
+
+    #[test]
+    pub fn test_sand_sinks_through_water() {
+        let mut g = Game::with_seed(64, 31);
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 8)..floor {
+            g.put_particle(19, y, ParticleKind::Concrete);
+            g.put_particle(30, y, ParticleKind::Concrete);
+        }
+        for x in 20..30 {
+            for y in (floor - 5)..floor {
+                g.put_particle(x, y, ParticleKind::Water);
+            }
+            g.put_particle(x, floor - 6, ParticleKind::Sand);
+        }
+        g.step(30);
+        assert_eq!(g.get_stats().count(ParticleKind::Water), 50);
+        assert_eq!(g.get_stats().count(ParticleKind::Sand), 10);
+        assert_eq!(heights(&g, ParticleKind::Sand)[20..30].iter().sum::<usize>(), 10);
+        for x in 0..g.get_n_cols() {
+            if g.get_kind(x, floor - 1) == ParticleKind::Water {
+                // no sand is left floating on the water
+                for y in 0..floor {
+                    assert_ne!(g.get_kind(x, y), ParticleKind::Sand);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_salt_dissolves_into_sinking_brine() {
+        let mut g = Game::with_seed(64, 32);
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 12)..floor {
+            g.put_particle(19, y, ParticleKind::Concrete);
+            g.put_particle(30, y, ParticleKind::Concrete);
+        }
+        for x in 20..30 {
+            for y in (floor - 10)..floor {
+                g.put_particle(x, y, ParticleKind::Water);
+            }
+        }
+        for x in 20..30 {
+            g.put_particle(x, floor - 11, ParticleKind::Salt);
+        }
+        g.step(200);
+
+        let stats = g.get_stats();
+        assert_eq!(stats.count(ParticleKind::Salt), 0);
+        assert_eq!(stats.count(ParticleKind::Brine) + stats.count(ParticleKind::Water), 100);
+        assert!(stats.count(ParticleKind::Brine) >= 10 / SATURATION as u32);
+        // brine is denser so ends up at the bottom
+        for x in 20..30 {
+            assert_eq!(g.get_kind(x, floor - 1), ParticleKind::Brine);
+        }
+    }
+
+    #[test]
+    pub fn test_exposed_brine_evaporates() {
+        let mut g = Game::with_seed(64, 32);
+        let floor = g.get_n_rows() - 1;
+        g.put_particle(19, floor - 1, ParticleKind::Concrete);
+        g.put_particle(20, floor - 1, ParticleKind::Brine);
+        g.put_particle(21, floor - 1, ParticleKind::Concrete);
+        g.step(5_000);
+        assert_eq!(g.get_kind(20, floor - 1), ParticleKind::Salt);
+    }
+
+    #[test]
+    pub fn test_drying_brine_keeps_its_salt() {
+        let mut g = Game::with_seed(64, 32);
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 6)..floor {
+            g.put_particle(19, y, ParticleKind::Concrete);
+            g.put_particle(30, y, ParticleKind::Concrete);
+        }
+        for x in 20..30 {
+            let brine = BrineParticle::with_salinity(SATURATION);
+            g.set_cell((floor - 1) * g.n_cols + x, Rc::new(RefCell::new(brine)));
+        }
+        let held = salt(&g);
+        assert_eq!(held, 10 * SATURATION as u32);
+        // some of it evaporates in the open, and then the rest boils dry
+        for _ in 0..50 {
+            g.step(100);
+            assert_eq!(salt(&g), held);
+        }
+        for y in (floor - 6)..floor {
+            for x in 20..30 {
+                g.set_temperature(x, y, 150.0);
+            }
+        }
+        for _ in 0..50 {
+            g.step(1);
+            assert_eq!(salt(&g), held);
+        }
+        assert_eq!(g.get_stats().count(ParticleKind::Brine), 0);
+        assert_eq!(g.get_stats().count(ParticleKind::Salt), held);
+    }
+
+    #[test]
+    pub fn test_wood_burns_away() {
+        let mut g = Game::with_seed(64, 33);
+        for x in 10..30 {
+            for y in 20..25 {
+                g.put_particle(x, y, ParticleKind::Wood);
+            }
+        }
+        g.put_particle(10, 19, ParticleKind::Fire);
+        let mut seen_smoke = false;
+        for _ in 0..2000 {
+            g.step(1);
+            seen_smoke |= g.get_stats().count(ParticleKind::Smoke) > 0;
+        }
+        let stats = g.get_stats();
+        assert_eq!(stats.count(ParticleKind::Wood), 0);
+        assert_eq!(stats.count(ParticleKind::Fire), 0);
+        assert!(seen_smoke);
+        assert!(stats.count(ParticleKind::Ash) > 0);
+    }
+
+    #[test]
+    pub fn test_water_puts_out_fire() {
+        let mut g = Game::with_seed(64, 33);
+        g.put_particle(20, 20, ParticleKind::Fire);
+        g.put_particle(20, 19, ParticleKind::Water);
+        g.step(1);
+        let stats = g.get_stats();
+        assert_eq!(stats.count(ParticleKind::Fire), 0);
+        assert_eq!(stats.count(ParticleKind::Water), 0);
+        assert_eq!(stats.count(ParticleKind::Steam), 1);
+    }
+
+    #[test]
+    pub fn test_smoke_rises_and_dissipates() {
+        let mut g = Game::with_seed(64, 34);
+        let floor = g.get_n_rows() - 1;
+        g.put_particle(30, floor - 1, ParticleKind::Smoke);
+        g.step(20);
+        let y = (0..floor).find(|y| (0..64).any(|x| g.get_kind(x, *y) == ParticleKind::Smoke));
+        assert!(y.unwrap() < floor - 5);
+        g.step(200);
+        assert_eq!(g.get_stats().count(ParticleKind::Smoke), 0);
+    }
+
+    #[test]
+    pub fn test_steam_condenses_under_ceiling() {
+        let mut g = Game::with_seed(64, 34);
+        // a closed box, with steam at the bottom
+        for x in 20..31 {
+            g.put_particle(x, 10, ParticleKind::Concrete);
+            g.put_particle(x, 20, ParticleKind::Concrete);
+        }
+        for y in 10..21 {
+            g.put_particle(20, y, ParticleKind::Concrete);
+            g.put_particle(30, y, ParticleKind::Concrete);
+        }
+        for x in 21..30 {
+            g.put_particle(x, 19, ParticleKind::Steam);
+        }
+        g.step(300);
+        let stats = g.get_stats();
+        assert_eq!(stats.count(ParticleKind::Steam), 0);
+        assert_eq!(stats.count(ParticleKind::Water), 9);
+    }
+
+    #[test]
+    pub fn test_heat_conducts_and_moves_with_particles() {
+        let mut g = Game::with_seed(64, 35);
+        let floor = g.get_n_rows() - 1;
+        g.set_temperature(10, floor, 500.0);
+        g.step(10);
+        assert!(g.get_temperature(11, floor) > AMBIENT_TEMPERATURE + 1.0);
+        assert!(g.get_temperature(10, floor) < 500.0);
+
+        g.put_particle(40, 0, ParticleKind::Sand);
+        g.set_temperature(40, 0, 80.0);
+        g.step(5);
+        assert_eq!(g.get_kind(40, 7), ParticleKind::Sand);
+        assert!(g.get_temperature(40, 7) > 70.0);
+        assert!(g.get_temperature(40, 0) < 30.0);
+    }
+
+    #[test]
+    pub fn test_phase_transitions() {
+        let mut g = Game::with_seed(64, 35);
+        let floor = g.get_n_rows() - 1;
+        for (x, kind, t) in [
+            (10, ParticleKind::Water, 150.0),
+            (20, ParticleKind::Water, -20.0),
+            (30, ParticleKind::Ice, 20.0),
+            (40, ParticleKind::Wood, 400.0),
+            (50, ParticleKind::Brine, 150.0)
+        ] {
+            g.put_particle(x - 1, floor - 1, ParticleKind::Concrete);
+            g.put_particle(x, floor - 1, kind);
+            g.put_particle(x + 1, floor - 1, ParticleKind::Concrete);
+            g.set_temperature(x, floor - 1, t);
+        }
+        g.step(1);
+        assert_eq!(g.get_kind(10, floor - 1), ParticleKind::Steam);
+        assert_eq!(g.get_kind(20, floor - 1), ParticleKind::Ice);
+        assert_eq!(g.get_kind(30, floor - 1), ParticleKind::Water);
+        assert_eq!(g.get_kind(40, floor - 1), ParticleKind::Fire);
+        assert_eq!(g.get_kind(50, floor - 1), ParticleKind::Salt);
+    }
+
+    #[test]
+    pub fn test_fire_heats_and_boils_water() {
+        let mut g = Game::with_seed(64, 35);
+        let floor = g.get_n_rows() - 1;
+        // a pot of water, over a fire that can't touch it
+        for x in 20..31 {
+            g.put_particle(x, floor - 2, ParticleKind::Concrete);
+        }
+        g.put_particle(20, floor - 3, ParticleKind::Concrete);
+        g.put_particle(30, floor - 3, ParticleKind::Concrete);
+        for x in 21..30 {
+            g.put_particle(x, floor - 3, ParticleKind::Water);
+        }
+        let mut boiled = false;
+        for _ in 0..2000 {
+            for x in 20..31 {
+                g.put_particle(x, floor - 1, ParticleKind::Fire);
+            }
+            g.step(1);
+            boiled |= g.get_stats().count(ParticleKind::Steam) > 0;
+        }
+        assert!(boiled);
+    }
+
+    #[test]
+    pub fn test_lava_meets_water() {
+        let mut g = Game::with_seed(64, 36);
+        let floor = g.get_n_rows() - 1;
+        g.put_particle(19, floor - 1, ParticleKind::Concrete);
+        g.put_particle(40, floor - 1, ParticleKind::Concrete);
+        for x in 20..40 {
+            g.put_particle(x, floor - 1, ParticleKind::Water);
+        }
+        for x in 25..35 {
+            g.put_particle(x, floor - 3, ParticleKind::Lava);
+        }
+        let mut boiled = false;
+        for _ in 0..200 {
+            g.step(1);
+            boiled |= g.get_stats().count(ParticleKind::Steam) > 0;
+        }
+        assert!(boiled);
+        assert!(g.get_stats().count(ParticleKind::Stone) > 0);
+    }
+
+    #[test]
+    pub fn test_lava_sets_into_stone_and_remelts() {
+        let mut g = Game::with_seed(64, 36);
+        let floor = g.get_n_rows() - 1;
+        for x in 20..40 {
+            g.put_particle(x, floor - 1, ParticleKind::Lava);
+        }
+        g.step(2000);
+        assert_eq!(g.get_stats().count(ParticleKind::Lava), 0);
+        assert_eq!(g.get_stats().count(ParticleKind::Stone), 20);
+
+        let x = (20..40).find(|&x| g.get_kind(x, floor - 1) == ParticleKind::Stone).unwrap();
+        g.set_temperature(x, floor - 1, 1500.0);
+        g.step(1);
+        assert_eq!(g.get_kind(x, floor - 1), ParticleKind::Lava);
+    }
+
+    #[test]
+    pub fn test_metal_melts() {
+        let mut g = Game::with_seed(64, 35);
+        let floor = g.get_n_rows() - 1;
+        for (x, kind) in [(20, ParticleKind::Wire), (30, ParticleKind::Heater), (40, ParticleKind::Switch)] {
+            g.put_particle(x, floor - 1, kind);
+            g.set_temperature(x, floor - 1, METAL_MELT_TEMPERATURE - 10.0);
+            g.step(1);
+            assert_eq!(g.get_kind(x, floor - 1), kind);
+            g.set_temperature(x, floor - 1, 1500.0);
+            g.step(1);
+            assert_eq!(g.get_kind(x, floor - 1), ParticleKind::Lava);
+        }
+    }
+
+    #[test]
+    pub fn test_lava_ignites_wood() {
+        let mut g = Game::with_seed(64, 36);
+        let floor = g.get_n_rows() - 1;
+        for x in 20..40 {
+            g.put_particle(x, floor - 1, ParticleKind::Wood);
+        }
+        g.put_particle(30, floor - 2, ParticleKind::Lava);
+        let mut lit = false;
+        for _ in 0..100 {
+            g.step(1);
+            lit |= g.get_stats().count(ParticleKind::Fire) > 0;
+        }
+        assert!(lit);
+    }
+
+    #[test]
+    pub fn test_oil_floats_on_water() {
+        let mut g = Game::with_seed(64, 37);
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 8)..floor {
+            g.put_particle(19, y, ParticleKind::Concrete);
+            g.put_particle(30, y, ParticleKind::Concrete);
+        }
+        // oil poured in underneath the water
+        for x in 20..30 {
+            g.put_particle(x, floor - 1, ParticleKind::Oil);
+            g.put_particle(x, floor - 2, ParticleKind::Oil);
+            g.put_particle(x, floor - 3, ParticleKind::Water);
+            g.put_particle(x, floor - 4, ParticleKind::Water);
+        }
+        g.step(300);
+        for x in 20..30 {
+            assert_eq!(g.get_kind(x, floor - 1), ParticleKind::Water);
+            assert_eq!(g.get_kind(x, floor - 2), ParticleKind::Water);
+            assert_eq!(g.get_kind(x, floor - 3), ParticleKind::Oil);
+            assert_eq!(g.get_kind(x, floor - 4), ParticleKind::Oil);
+        }
+    }
+
+    #[test]
+    pub fn test_oil_spreads_slower_than_water() {
+        let spread = |kind| {
+            let mut g = Game::with_seed(128, 37);
+            let floor = g.get_n_rows() - 1;
+            for y in (floor - 20)..floor {
+                g.put_particle(64, y, kind);
+            }
+            g.step(40);
+            let xs: Vec<usize> = (0..g.get_n_cols()).filter(|&x| g.get_kind(x, floor - 1) == kind).collect();
+            xs[xs.len() - 1] - xs[0]
+        };
+        let (oil, water) = (spread(ParticleKind::Oil), spread(ParticleKind::Water));
+        assert!(oil < water);
+    }
+
+    #[test]
+    pub fn test_oil_burns() {
+        let mut g = Game::with_seed(64, 37);
+        let floor = g.get_n_rows() - 1;
+        for x in 10..50 {
+            g.put_particle(x, floor - 1, ParticleKind::Concrete);
+            g.put_particle(x, floor - 2, ParticleKind::Oil);
+        }
+        g.put_particle(10, floor - 3, ParticleKind::Fire);
+        g.step(500);
+        assert!(g.get_stats().count(ParticleKind::Oil) < 10);
+    }
+
+    #[test]
+    pub fn test_acid_eats_wood_faster_than_concrete() {
+        let eaten = |kind| {
+            let mut g = Game::with_seed(64, 38);
+            let floor = g.get_n_rows() - 1;
+            for y in (floor - 10)..floor {
+                for x in 20..40 {
+                    g.put_particle(x, y, kind);
+                }
+            }
+            for x in 25..35 {
+                g.put_particle(x, floor - 11, ParticleKind::Acid);
+            }
+            let before = g.get_stats().count(kind);
+            g.step(100);
+            before - g.get_stats().count(kind)
+        };
+        assert!(eaten(ParticleKind::Wood) > eaten(ParticleKind::Concrete));
+    }
+
+    #[test]
+    pub fn test_acid_is_used_up_and_held_by_glass() {
+        let mut g = Game::with_seed(64, 38);
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 6)..floor {
+            g.put_particle(19, y, ParticleKind::Glass);
+            g.put_particle(30, y, ParticleKind::Glass);
+        }
+        for x in 20..30 {
+            g.put_particle(x, floor - 1, ParticleKind::Glass);
+            g.put_particle(x, floor - 2, ParticleKind::Acid);
+            g.put_particle(x, floor - 3, ParticleKind::Salt);
+        }
+        g.step(200);
+        assert_eq!(g.get_stats().count(ParticleKind::Glass), 22);
+        assert_eq!(g.get_stats().count(ParticleKind::Salt), 0);
+        // ten acid, four grains of salt each at most
+        assert!(g.get_stats().count(ParticleKind::Acid) >= 10 - 10 / STRENGTH as u32);
+    }
+
+    #[test]
+    pub fn test_ice_freezes_water() {
+        let mut g = Game::with_seed(64, 39);
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 6)..floor {
+            g.put_particle(19, y, ParticleKind::Concrete);
+            g.put_particle(30, y, ParticleKind::Concrete);
+            for x in 20..30 {
+                g.put_particle(x, y, ParticleKind::Water);
+                g.set_temperature(x, y, 1.0);
+            }
+        }
+        for y in (floor - 4)..(floor - 1) {
+            for x in 24..27 {
+                g.put_particle(x, y, ParticleKind::Ice);
+                g.set_temperature(x, y, -50.0);
+            }
+        }
+        let mut most_ice = 0;
+        for _ in 0..500 {
+            g.step(1);
+            most_ice = most_ice.max(g.get_stats().count(ParticleKind::Ice));
+        }
+        assert!(most_ice > 9);
+    }
+
+    #[test]
+    pub fn test_frozen_water_keeps_its_temperature() {
+        let mut g = Game::with_seed(64, 39);
+        let y = g.get_n_rows() - 2;
+        g.put_particle(20, y, ParticleKind::Ice);
+        g.set_temperature(20, y, -50.0);
+        g.put_particle(19, y, ParticleKind::Water);
+        g.set_temperature(19, y, 3.0);
+        g.put_particle(21, y, ParticleKind::Water);
+        g.set_temperature(21, y, -0.5);
+        let mut blasts = Vec::new();
+        g.act(20, y as i32, Action::Freeze{x: -1, y: 0}, &mut blasts);
+        g.act(20, y as i32, Action::Freeze{x: 1, y: 0}, &mut blasts);
+        // frozen at freezing point, not as cold as new ice
+        assert_eq!(g.get_kind(19, y), ParticleKind::Ice);
+        assert_eq!(g.get_temperature(19, y), 0.0);
+        assert_eq!(g.get_kind(21, y), ParticleKind::Ice);
+        assert_eq!(g.get_temperature(21, y), -0.5);
+    }
+
+    #[test]
+    pub fn test_snow_settles_and_packs_into_ice() {
+        let mut g = Game::with_seed(64, 39);
+        for y in 0..g.get_n_rows() {
+            for x in 0..g.get_n_cols() {
+                g.set_temperature(x, y, -20.0);
+            }
+        }
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 10)..(floor - 5) {
+            for x in 20..40 {
+                g.put_particle(x, y, ParticleKind::Snow);
+            }
+        }
+        g.step(100);
+        assert_eq!(g.get_stats().count(ParticleKind::Snow), 100);
+        g.step(1000);
+        assert!(g.get_stats().count(ParticleKind::Ice) > 0);
+        assert!(g.get_stats().count(ParticleKind::Snow) > 0);
+        assert!((0..g.get_n_cols()).any(|x| g.get_kind(x, floor - 1) == ParticleKind::Ice));
+    }
+
+    #[test]
+    pub fn test_snow_melts() {
+        let mut g = Game::with_seed(64, 39);
+        g.put_particle(30, 0, ParticleKind::Snow);
+        g.step(2000);
+        assert_eq!(g.get_stats().count(ParticleKind::Snow), 0);
+    }
+
+    #[test]
+    pub fn test_tnt_explodes() {
+        let mut g = Game::with_seed(64, 40);
+        let floor = g.get_n_rows() - 1;
+        for y in (floor - 10)..floor {
+            for x in 10..50 {
+                g.put_particle(x, y, ParticleKind::Sand);
+            }
+        }
+        g.put_particle(30, floor - 5, ParticleKind::Tnt);
+        g.set_temperature(30, floor - 5, 500.0);
+        let sand = g.get_stats().count(ParticleKind::Sand);
+        g.step(1);
+        assert_eq!(g.get_stats().explosions, 1);
+        assert_eq!(g.get_stats().count(ParticleKind::Tnt), 0);
+        assert!(g.get_stats().count(ParticleKind::Sand) < sand);
+        // nothing soft is left right next to the centre
+        for (dx, dy) in NEIGHBOUR_OFFSETS {
+            let kind = g.get_kind((30 + dx) as usize, (floor as i32 - 5 + dy) as usize);
+            assert!(kind == ParticleKind::Background || kind == ParticleKind::Fire);
+        }
+        // but the floor holds
+        assert!((0..g.get_n_cols()).all(|x| g.get_kind(x, floor) == ParticleKind::Concrete));
+    }
+
+    #[test]
+    pub fn test_blast_flings_and_chains() {
+        let mut g = Game::with_seed(64, 40);
+        let floor = g.get_n_rows() - 1;
+        g.put_particle(20, floor - 1, ParticleKind::Tnt);
+        g.put_particle(24, floor - 1, ParticleKind::Tnt);
+        // a shelf with some ash on it, which would otherwise fall
+        for x in 17..24 {
+            g.put_particle(x, floor - 6, ParticleKind::Concrete);
+        }
+        g.put_particle(20, floor - 7, ParticleKind::Ash);
+        g.put_particle(20, floor - 2, ParticleKind::Fire);
+        g.step(1);
+        assert_eq!(g.get_stats().explosions, 1);
+        // the ash was thrown further up
+        assert_eq!(g.get_kind(20, floor - 7), ParticleKind::Background);
+        assert!((0..(floor - 7)).any(|y| g.get_kind(20, y) == ParticleKind::Ash));
+        g.step(1);
+        assert_eq!(g.get_stats().explosions, 1);
+        assert_eq!(g.get_stats().count(ParticleKind::Tnt), 0);
+    }
+
+    #[test]
+    pub fn test_gunpowder_fuse() {
+        let mut g = Game::with_seed(64, 40);
+        let floor = g.get_n_rows() - 1;
+        for x in 10..40 {
+            g.put_particle(x, floor - 1, ParticleKind::Gunpowder);
+        }
+        g.put_particle(40, floor - 1, ParticleKind::Tnt);
+        g.put_particle(10, floor - 2, ParticleKind::Fire);
+        let mut exploded = false;
+        for _ in 0..300 {
+            g.step(1);
+            exploded |= g.get_stats().explosions > 0;
+        }
+        assert!(exploded);
+        assert_eq!(g.get_stats().count(ParticleKind::Gunpowder), 0);
+    }
+
+    #[test]
+    pub fn test_seed_sprouts_and_grows_on_water() {
+        let mut g = Game::with_seed(64, 41);
+        let floor = g.get_n_rows() - 1;
+        g.put_particle(17, floor - 1, ParticleKind::Concrete);
+        g.put_particle(18, floor - 1, ParticleKind::Soil);
+        g.put_particle(19, floor - 1, ParticleKind::Soil);
+        g.put_particle(30, floor - 1, ParticleKind::Concrete);
+        for x in 20..30 {
+            g.put_particle(x, floor - 1, ParticleKind::Water);
+        }
+        g.put_particle(19, floor - 10, ParticleKind::Seed);
+        // a seed on sand never sprouts
+        g.put_particle(40, floor - 1, ParticleKind::Sand);
+        g.put_particle(41, floor - 1, ParticleKind::Water);
+        g.put_particle(40, floor - 10, ParticleKind::Seed);
+
+        g.step(1000);
+        assert_eq!(g.get_stats().count(ParticleKind::Seed), 1);
+        assert!(g.get_stats().count(ParticleKind::Plant) > 5);
+        // it has both stem and leaves
+        let parts: Vec<u64> = g.cells.iter()
+            .filter(|c| c.borrow().get_type() == ParticleKind::Plant)
+            .map(|c| (c.borrow().get_state() >> 32) & 0xff)
+            .collect();
+        assert!(parts.contains(&(Part::Stem as u64)));
+        assert!(parts.contains(&(Part::Leaf as u64)));
+        // and the plant drank from the pool
+        assert!(g.get_stats().count(ParticleKind::Water) < 11);
+    }
+
+    #[test]
+    pub fn test_plants_burn() {
+        let mut g = Game::with_seed(64, 42);
+        let floor = g.get_n_rows() - 1;
+        for x in 10..40 {
+            g.put_particle(x, floor - 1, ParticleKind::Plant);
+        }
+        for x in 10..13 {
+            g.put_particle(x, floor - 2, ParticleKind::Fire);
+        }
+        g.step(500);
+        let left = (10..40).filter(|&x| g.get_kind(x, floor - 1) == ParticleKind::Plant).count();
+        assert!(left < 15);
+    }
+
+    /// A battery at the left, then a wire running right through a switch to
+    /// a lamp and a heater, on row `y`.
+    fn circuit(g: &mut Game, y: usize) {
+        g.put_particle(10, y, ParticleKind::Battery);
+        for x in 11..30 {
+            g.put_particle(x, y, ParticleKind::Wire);
+        }
+        g.put_particle(20, y, ParticleKind::Switch);
+        g.put_particle(30, y, ParticleKind::Lamp);
+        g.put_particle(29, y - 1, ParticleKind::Heater);
+    }
+
+    #[test]
+    pub fn test_switch_controls_circuit() {
+        let mut g = Game::with_seed(64, 42);
+        let y = g.get_n_rows() - 5;
+        circuit(&mut g, y);
+        g.step(50);
+        assert_eq!(g.get_kind(30, y), ParticleKind::Lamp);
+        assert_eq!(g.cells[y*g.n_cols + 30].borrow().get_colour().as_i(), LAMP.as_i());
+        assert!(g.get_temperature(29, y - 1) < 30.0);
+
+        g.toggle(20, y);
+        g.step(50);
+        assert_eq!(g.cells[y*g.n_cols + 30].borrow().get_colour().as_i(), YELLOW.as_i());
+        assert!(g.get_temperature(29, y - 1) > 100.0);
+
+        // pulses show on the wire
+        g.render();
+        let heads = (11..30).filter(|&x| {
+            let idx = (y*g.n_cols + x) * 4;
+            g.output_buffer[idx..idx + 4] == ELECTRON_HEAD.as_uarr()
+        }).count();
+        assert!(heads > 0);
+
+        g.toggle(20, y);
+        g.step(50);
+        assert_eq!(g.cells[y*g.n_cols + 30].borrow().get_colour().as_i(), LAMP.as_i());
+    }
+
+    #[test]
+    pub fn test_heater_block_heats_steadily() {
+        let mut g = Game::with_seed(64, 42);
+        let y = g.get_n_rows() - 5;
+        circuit(&mut g, y);
+        for by in y - 3..y {
+            for bx in 27..30 {
+                g.put_particle(bx, by, ParticleKind::Heater);
+            }
+        }
+        g.toggle(20, y);
+        for _ in 0..30 {
+            g.step(10);
+            assert!(g.temperature.iter().all(|t| t.is_finite()));
+            for by in y - 3..y {
+                for bx in 27..30 {
+                    let t = g.get_temperature(bx, by);
+                    assert!((AMBIENT_TEMPERATURE - 1.0..=heater::MAX_TEMPERATURE).contains(&t), "{} at {},{}", t, bx, by);
+                }
+            }
+        }
+        // the whole block has warmed through, from the bottom up
+        assert!(g.get_temperature(28, y - 3) > 100.0);
+        assert!(g.get_temperature(28, y - 1) >= g.get_temperature(28, y - 3));
+    }
+
+    #[test]
+    pub fn test_replay_toggles() {
+        let mut g = Game::with_seed(64, 42);
+        let y = g.get_n_rows() - 5;
+        g.set_brush(ParticleKind::Wire);
+        for x in 11..30 {
+            g.paint(x, y);
+        }
+        g.set_brush(ParticleKind::Battery);
+        g.paint(10, y);
+        g.set_brush(ParticleKind::Switch);
+        g.paint(20, y);
+        g.step(5);
+        g.toggle(20, y);
+        g.step(17);
+        let replayed = Game::replay(&g.get_recording()).unwrap();
+        assert_eq!(replayed.state_hash(), g.state_hash());
+    }
+
+    #[test]
+    pub fn test_sources_and_drains() {
+        let mut g = Game::with_seed(64, 43);
+        let floor = g.get_n_rows() - 1;
+        g.set_source(ParticleKind::Sand, 1.0);
+        g.set_brush(ParticleKind::Source);
+        g.paint(20, 5);
+        g.set_source(ParticleKind::Oil, 0.1);
+        g.paint(40, 5);
+        g.step(100);
+        let sand = g.get_stats().count(ParticleKind::Sand);
+        let oil = g.get_stats().count(ParticleKind::Oil);
+        assert!(sand > 80);
+        assert!(oil > 0 && oil < sand / 2);
+
+        // a drain in the floor swallows what lands on it
+        g.set_brush(ParticleKind::Drain);
+        for x in 15..26 {
+            g.paint(x, floor);
+        }
+        g.set_brush(ParticleKind::Background);
+        g.paint(20, 5);
+        let moved = g.get_stats().count(ParticleKind::Sand);
+        g.step(300);
+        assert!(g.get_stats().count(ParticleKind::Sand) < moved);
+        assert_eq!(g.get_stats().count(ParticleKind::Drain), 11);
+        // and leaves the concrete around it alone
+        assert_eq!(g.get_kind(14, floor), ParticleKind::Concrete);
+        assert_eq!(g.get_kind(26, floor), ParticleKind::Concrete);
+
+        let replayed = Game::replay(&g.get_recording()).unwrap();
+        assert_eq!(replayed.state_hash(), g.state_hash());
+    }
+
+    #[test]
+    pub fn test_painted_cornucopia() {
+        let mut g = Game::with_seed(64, 44);
+        g.set_cornucopia(ParticleKind::Sand, 0.5, 0, 1, ParticleKind::Water);
+        g.set_brush(ParticleKind::Cornucopia);
+        g.paint(20, 5);
+        g.step(100);
+        let sand = g.get_stats().count(ParticleKind::Sand);
+        assert!((30..70).contains(&sand));
+        assert_eq!(g.get_stats().count(ParticleKind::Cornucopia), 1);
+
+        let replayed = Game::replay(&g.get_recording()).unwrap();
+        assert_eq!(replayed.state_hash(), g.state_hash());
+    }
+
+    #[test]
+    pub fn test_synchronous_sand_sinks_through_water() {
+        let mut g = Game::with_seed(64, 46);
+        g.set_synchronous(true);
+        let floor = g.get_n_rows() - 1;
+        g.set_brush(ParticleKind::Concrete);
+        for y in (floor - 8)..floor {
+            g.paint(19, y);
+            g.paint(30, y);
+        }
+        for x in 20..30 {
+            g.set_brush(ParticleKind::Water);
+            for y in (floor - 5)..floor {
+                g.paint(x, y);
+            }
+            g.set_brush(ParticleKind::Sand);
+            g.paint(x, floor - 6);
+        }
+        g.step(60);
+        // whatever clashed, nothing was lost
+        assert_eq!(g.get_stats().count(ParticleKind::Water), 50);
+        assert_eq!(g.get_stats().count(ParticleKind::Sand), 10);
+        assert_eq!(heights(&g, ParticleKind::Sand)[20..30].iter().sum::<usize>(), 10);
+
+        let replayed = Game::replay(&g.get_recording()).unwrap();
+        assert!(replayed.is_synchronous());
+        assert_eq!(replayed.state_hash(), g.state_hash());
+    }
+
+    /// Grains of salt in the world, whether lying about or dissolved.
+    fn salt(g: &Game) -> u32 {
+        let dissolved: u64 = g.cells.iter()
+            .filter(|c| c.borrow().get_type() == ParticleKind::Brine)
+            .map(|c| c.borrow().get_state() >> 32)
+            .sum();
+        g.get_stats().count(ParticleKind::Salt) + dissolved as u32
+    }
+
+    #[test]
+    pub fn test_synchronous_conflict_conserves_salt() {
+        // two brines boxed in either side of a single grain of salt, both of
+        // which try to dissolve it
+        let mut g = Game::with_seed(64, 42);
+        g.set_synchronous(true);
+        let y = g.get_n_rows() - 2;
+        for x in 9..14 {
+            g.put_particle(x, y - 1, ParticleKind::Concrete);
+        }
+        g.put_particle(9, y, ParticleKind::Concrete);
+        g.put_particle(13, y, ParticleKind::Concrete);
+        g.put_particle(10, y, ParticleKind::Brine);
+        g.put_particle(11, y, ParticleKind::Salt);
+        g.put_particle(12, y, ParticleKind::Brine);
+        assert_eq!(salt(&g), 3);
+        for _ in 0..10 {
+            g.step(1);
+            assert_eq!(salt(&g), 3);
+        }
+        assert!(g.get_stats().conflicts > 0);
+        assert_eq!(g.get_stats().count(ParticleKind::Salt), 0);
+    }
+
+    #[test]
+    pub fn test_synchronous_conflict() {
+        // two grains on pedestals either side of a gap, each of which may
+        // slide into it
+        let mut clashed = false;
+        for seed in 0..20 {
+            let mut g = Game::with_seed(64, seed);
+            g.set_synchronous(true);
+            for x in [9, 10, 12, 13] {
+                g.put_particle(x, 6, ParticleKind::Concrete);
+            }
+            g.put_particle(11, 7, ParticleKind::Concrete);
+            g.put_particle(10, 5, ParticleKind::Sand);
+            g.put_particle(12, 5, ParticleKind::Sand);
+            g.step(1);
+            assert_eq!(g.get_stats().count(ParticleKind::Sand), 2);
+            if g.get_stats().conflicts > 0 {
+                clashed = true;
+                // the grain scanned first gets there
+                assert_eq!(g.get_kind(11, 6), ParticleKind::Sand);
+                assert_eq!(g.get_kind(10, 5), ParticleKind::Background);
+                assert_eq!(g.get_kind(12, 5), ParticleKind::Sand);
+            }
+        }
+        assert!(clashed);
+    }
+
+    #[test]
+    pub fn test_falls_pick_up_speed() {
+        let mut g = Game::with_seed(64, 48);
+        for x in 18..23 {
+            g.put_particle(x, 20, ParticleKind::Concrete);
+        }
+        g.put_particle(20, 0, ParticleKind::Sand);
+        g.put_particle(40, 0, ParticleKind::Sand);
+        g.step(10);
+        // 1, 1, 1, 2, 2, 3, 3, 4, 4 and 5 cells
+        assert_eq!(g.get_kind(40, 26), ParticleKind::Sand);
+        // but never through anything
+        assert_eq!(g.get_kind(20, 19), ParticleKind::Sand);
+        assert_eq!(g.velocity[19*g.n_cols + 20], (0.0, 0.0));
+    }
+
+    #[test]
+    pub fn test_landing_splashes() {
+        let mut g = Game::with_seed(64, 48);
+        let floor = g.get_n_rows() - 1;
+        g.put_particle(9, floor - 1, ParticleKind::Concrete);
+        g.put_particle(30, floor - 1, ParticleKind::Concrete);
+        g.put_particle(20, 0, ParticleKind::Water);
+        g.step(13);
+        assert_eq!(g.get_kind(20, floor - 1), ParticleKind::Water);
+        // it hit the floor hard enough to go skidding off sideways
+        let idx = (floor - 1)*g.n_cols + 20;
+        assert!(g.velocity[idx].0.abs() > 3.0);
+        g.step(1);
+        assert_eq!(g.get_kind(20, floor - 1), ParticleKind::Background);
+        assert!((10..30).any(|x: usize| x.abs_diff(20) >= 3 && g.get_kind(x, floor - 1) == ParticleKind::Water));
+    }
+
+    #[test]
+    pub fn test_gravity_keeps_everything_in_the_world() {
+        let mut g = Game::with_seed(64, 49);
+        for y in 10..20 {
+            for x in 10..20 {
+                g.put_particle(x, y, ParticleKind::Sand);
+                g.put_particle(x + 30, y, ParticleKind::Water);
+            }
+        }
+        let counts: Vec<u32> = ParticleKind::ALL.iter().map(|k| g.get_stats().count(*k)).collect();
+        for (x, y) in [(0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1), (1, 0), (1, 1)] {
+            g.set_gravity(x as f32, y as f32, 2.0);
+            g.step(100);
+            let now: Vec<u32> = ParticleKind::ALL.iter().map(|k| g.get_stats().count(*k)).collect();
+            assert_eq!(now, counts, "falling {:?}", (x, y));
+            // and what's piled up against the edge has come to a stop
+            for (idx, cell) in g.cells.iter().enumerate() {
+                if matches!(cell.borrow().get_type(), ParticleKind::Sand | ParticleKind::Water) {
+                    let (vx, vy) = g.velocity[idx];
+                    assert!(vx.abs() < motion::IMPACT_SPEED && vy.abs() < motion::IMPACT_SPEED, "falling {:?} {:?}", (x, y), (vx, vy));
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_gravity_direction() {
+        let mut g = Game::with_seed(64, 49);
+        g.set_brush(ParticleKind::Sand);
+        g.paint(30, 10);
+        g.set_gravity(-1.0, 0.0, 1.0);
+        g.step(5);
+        // 1, 1, 1, 2 and 2 cells, leftwards
+        assert_eq!(g.get_kind(23, 10), ParticleKind::Sand);
+
+        g.set_gravity(1.0, 1.2, 1.0);
+        assert_eq!((g.get_gravity_x(), g.get_gravity_y()), (1, 1));
+        g.step(3);
+        assert_eq!(g.get_kind(26, 13), ParticleKind::Sand);
+
+        // a pile against a wall, on its side
+        g.set_gravity(1.0, 0.0, 1.0);
+        g.set_brush(ParticleKind::Concrete);
+        for y in 0..g.get_n_rows() - 1 {
+            g.paint(50, y);
+        }
+        g.set_brush(ParticleKind::Sand);
+        for y in 10..20 {
+            g.paint(40, y);
+        }
+        g.step(60);
+        assert_eq!(g.get_stats().count(ParticleKind::Sand), 11);
+        assert!((10..20).all(|y| g.get_kind(49, y) == ParticleKind::Sand));
+
+        let replayed = Game::replay(&g.get_recording()).unwrap();
+        assert_eq!(replayed.get_gravity_x(), 1);
+        assert_eq!(replayed.state_hash(), g.state_hash());
+    }
+
+    #[test]
+    pub fn test_zero_gravity() {
+        let mut g = Game::with_seed(64, 49);
+        g.put_particle(10, 10, ParticleKind::Sand);
+        g.put_particle(20, 10, ParticleKind::Water);
+        g.put_particle(30, 10, ParticleKind::Smoke);
+        g.set_gravity(0.0, 0.0, 1.0);
+        g.step(10);
+        assert_eq!(g.get_kind(10, 10), ParticleKind::Sand);
+        assert_eq!(g.get_kind(20, 10), ParticleKind::Water);
+        assert_eq!(g.get_kind(30, 10), ParticleKind::Smoke);
+
+        g.set_gravity(0.0, 1.0, 0.5);
+        g.step(4);
+        // falling at half the usual rate: 1, 1, 1 and 1 cells
+        assert_eq!(g.get_kind(10, 14), ParticleKind::Sand);
+    }
+
+    #[test]
+    pub fn test_wind() {
+        let mut g = Game::with_seed(64, 50);
+        let floor = g.get_n_rows() - 2;
+        g.put_particle(10, 10, ParticleKind::Smoke);
+        g.put_particle(10, floor, ParticleKind::Sand);
+        g.put_particle(30, floor, ParticleKind::Ash);
+        g.put_particle(50, floor, ParticleKind::Concrete);
+        g.set_uniform_wind(1.0, 0.0);
+        g.step(10);
+        // smoke goes where the wind takes it
+        assert_eq!(g.get_kind(20, 10), ParticleKind::Smoke);
+        // light ash is blown further along the floor than sand, and concrete
+        // not at all
+        let xs = |kind| (0..g.get_n_cols()).filter(|x| g.get_kind(*x, floor) == kind).collect::<Vec<usize>>();
+        let (ash, sand) = (xs(ParticleKind::Ash), xs(ParticleKind::Sand));
+        assert!(ash[0] - 30 > sand[0] - 10);
+        assert_eq!(g.get_kind(50, floor), ParticleKind::Concrete);
+
+        // and rises as usual once it dies down
+        g.set_uniform_wind(0.0, 0.0);
+        g.step(10);
+        assert!((0..10).any(|y| (0..g.get_n_cols()).any(|x| g.get_kind(x, y) == ParticleKind::Smoke)));
+    }
+
+    #[test]
+    pub fn test_wind_brush_and_vortex() {
+        let mut g = Game::with_seed(64, 50);
+        g.set_wind_brush(0.0, -1.0);
+        assert!(g.is_painting_wind());
+        g.paint(30, 20);
+        assert_eq!(g.get_kind(30, 20), ParticleKind::Background);
+        assert_eq!(g.wind[20*g.n_cols + 32], (0.0, -1.0));
+        g.add_vortex(10, 10, 5, 1.0);
+
+        g.set_brush(ParticleKind::Sand);
+        assert!(!g.is_painting_wind());
+        g.paint(30, 20);
+        g.set_brush(ParticleKind::Smoke);
+        g.paint(13, 10);
+
+        g.set_show_wind(true);
+        g.render();
+        let idx = (20*g.n_cols + 32) * 4;
+        assert_eq!(g.output_buffer[idx..idx + 4], wind::overlay(&BLACK, (0.0, -1.0)).as_uarr());
+
+        g.step(20);
+        let replayed = Game::replay(&g.get_recording()).unwrap();
+        assert_eq!(replayed.state_hash(), g.state_hash());
+    }
+
+    #[test]
+    pub fn test_conflict_policies() {
+        // a grain of sand sliding off a pedestal and a grain of salt falling
+        // straight down, both into the same cell
+        let winner = |policy: ConflictPolicy| {
+            let mut winners = Vec::new();
+            for seed in 0..20 {
+                let mut g = Game::with_seed(64, seed);
+                g.set_synchronous(true);
+                g.set_conflict_policy(policy);
+                g.put_particle(9, 5, ParticleKind::Concrete);
+                g.put_particle(10, 5, ParticleKind::Concrete);
+                g.put_particle(11, 6, ParticleKind::Concrete);
+                g.put_particle(10, 4, ParticleKind::Sand);
+                g.put_particle(11, 4, ParticleKind::Salt);
+                g.step(1);
+                assert_eq!(g.get_stats().count(ParticleKind::Sand), 1);
+                assert_eq!(g.get_stats().count(ParticleKind::Salt), 1);
+                if g.get_stats().conflicts > 0 {
+                    winners.push(g.get_kind(11, 5));
+                }
+            }
+            assert!(!winners.is_empty());
+            winners
+        };
+        assert!(winner(ConflictPolicy::FirstWins).iter().all(|k| *k == ParticleKind::Sand));
+        assert!(winner(ConflictPolicy::Heaviest).iter().all(|k| *k == ParticleKind::Salt));
+        assert!(winner(ConflictPolicy::Momentum).iter().all(|k| *k == ParticleKind::Sand));
+    }
+
+    #[test]
+    pub fn test_moves_never_overwrite() {
+        let mut g = Game::with_seed(64, 47);
+        g.put_particle(10, 5, ParticleKind::Sand);
+        g.put_particle(10, 6, ParticleKind::Concrete);
+        g.stats.begin_tick();
+        g.act(10, 5, Action::MoveInto{x: 0, y: 1}, &mut Vec::new());
+        assert_eq!(g.get_kind(10, 5), ParticleKind::Sand);
+        assert_eq!(g.get_kind(10, 6), ParticleKind::Concrete);
+        assert_eq!(g.get_stats().overwrites, 1);
+        assert_eq!(g.get_stats().moves, 0);
+    }
+
+    #[test]
+    pub fn test_life_in_the_world() {
+        let mut g = Game::with_seed(64, 45);
+        for y in 10..13 {
+            g.put_particle(20, y, ParticleKind::Life);
+        }
+        // births never overwrite other particles
+        for y in 10..13 {
+            g.put_particle(40, y, ParticleKind::Life);
+        }
+        g.put_particle(39, 11, ParticleKind::Concrete);
+        g.step(1);
+        assert_eq!(g.get_kind(21, 11), ParticleKind::Life);
+        assert_eq!(g.get_kind(20, 10), ParticleKind::Background);
+        assert_eq!(g.get_kind(39, 11), ParticleKind::Concrete);
+        assert_eq!(g.get_kind(41, 11), ParticleKind::Life);
+        g.step(1);
+        assert_eq!(g.get_kind(20, 10), ParticleKind::Life);
+        assert_eq!(g.get_kind(21, 11), ParticleKind::Background);
+
+        assert!(g.set_life_rule("B3/S").is_ok());
+        assert!(g.set_life_rule("nonsense").is_err());
+        assert_eq!(g.get_life_rule(), "B3/S");
+        g.step(1);
+        // nothing survives, so the blinker turns into its two births
+        assert_eq!(g.get_kind(20, 11), ParticleKind::Background);
+        assert_eq!(g.get_kind(19, 11), ParticleKind::Life);
+        assert_eq!(g.get_kind(21, 11), ParticleKind::Life);
+    }
+
+    #[test]
+    pub fn test_brians_brain_in_the_world() {
+        let mut g = Game::with_seed(64, 45);
+        g.set_life_rule(life::BRIANS_BRAIN).unwrap();
+        g.set_brush(ParticleKind::Life);
+        g.paint(20, 10);
+        g.paint(21, 10);
+        g.step(1);
+        assert_eq!(g.get_stats().count(ParticleKind::Life), 6);
+        g.render();
+        let idx = (10*g.n_cols + 20) * 4;
+        assert_eq!(g.output_buffer[idx..idx + 4], LIFE_DYING.as_uarr());
+
+        let replayed = Game::replay(&g.get_recording()).unwrap();
+        assert_eq!(replayed.state_hash(), g.state_hash());
+    }
+
+    #[test]
+    pub fn test_stats() {
+        let mut g = Game::new();
+        let n_cells = (g.get_n_rows() * g.get_n_cols()) as u32;
+        let stats = g.get_stats();
+        assert_eq!(stats.count(ParticleKind::Concrete), g.get_n_cols() as u32);
+        assert_eq!(stats.counts().iter().sum::<u32>(), n_cells);
+
+        g.put_particle(10, 0, ParticleKind::Salt);
+        g.put_particle(20, 0, ParticleKind::Salt);
+        g.put_particle(20, 10, ParticleKind::Water);
+        g.update();
+        let stats = g.get_stats();
+        assert_eq!(stats.tick, 1);
+        assert_eq!(stats.count(ParticleKind::Salt), 2);
+        assert_eq!(stats.moves, 3);
+        assert_eq!(stats.active, 3);
+
+        // salt touching water dissolves into brine
+        g.put_particle(21, 11, ParticleKind::Salt);
+        g.update();
+        let stats = g.get_stats();
+        assert_eq!(stats.reactions, 1);
+        assert_eq!(stats.count(ParticleKind::Salt), 2);
+        assert_eq!(stats.count(ParticleKind::Water), 0);
+        assert_eq!(stats.count(ParticleKind::Brine), 1);
+        assert_eq!(stats.counts().iter().sum::<u32>(), n_cells);
+    }
+
+    #[test]
+    pub fn test_replay() {
+        let mut g = Game::with_seed(64, 1234);
+        g.set_brush(ParticleKind::Water);
+        for i in 0..20 {
+            g.clicked(10 + i, 5);
+            g.update();
+        }
+        let (salt, _) = g.palette.iter().find(|(_, k)| **k == ParticleKind::Salt).unwrap();
+        g.clicked(salt.0, salt.1); // select salt from the menu
+        for i in 0..20 {
+            g.clicked(30, 2);
+            g.clicked(31 + i % 3, 2);
+            g.step(2);
+        }
+        g.set_brush(ParticleKind::Cornucopia);
+        g.clicked(50, 20);
+        g.step(30);
+
+        let recording = g.get_recording();
+        let replayed = Game::replay(&recording).unwrap();
+        assert_eq!(replayed.get_stats().tick, g.get_stats().tick);
+        assert_eq!(replayed.get_brush(), ParticleKind::Cornucopia);
+        assert_eq!(replayed.state_hash(), g.state_hash());
+
+        // and replaying a replay gives the same recording back
+        let mut replayed = replayed;
+        assert_eq!(replayed.get_recording(), recording);
+    }
+
+    #[test]
+    pub fn test_replay_rejects_malformed_recordings() {
+        let mut g = Game::with_seed(64, 1234);
+        g.set_brush(ParticleKind::Sand);
+        g.paint(10, 5);
+        let recording = g.get_recording();
+        assert!(Game::replay(&recording).is_ok());
+
+        for canvas in ["0", "24", &(MIN_CANVAS_SIZE - 1).to_string(), &(MAX_REPLAY_CANVAS_SIZE + 1).to_string(),
+                &usize::MAX.to_string()] {
+            assert!(Game::replay(&recording.replace("canvas 64", &format!("canvas {}", canvas))).is_err());
+        }
+        for event in ["paint 64 5", "paint 10 40", "toggle 64 0", "toggle 0 40"] {
+            assert!(Game::replay(&recording.replace("paint 10 5", event)).is_err(), "{}", event);
+        }
+        let end = recording.lines().find(|l| l.starts_with("end ")).unwrap();
+        for tick in [MAX_REPLAY_TICKS + 1, u32::MAX] {
+            assert!(Game::replay(&recording.replace(end, &format!("end {}", tick))).is_err());
+            let late = recording.replacen("\n0 ", &format!("\n{} ", tick), 1);
+            assert_ne!(late, recording);
+            assert!(Game::replay(&late).is_err());
+        }
+
+        // painting outside the game area does nothing, and isn't recorded
+        g.paint(64, 5);
+        g.paint(10, 1000);
+        assert_eq!(g.get_recording(), recording);
+    }
+
+    #[test]
+    pub fn test_hashes() {
+        let mut a = Game::with_seed(64, 1);
+        let mut b = Game::with_seed(64, 2);
+        assert_eq!(a.state_hash(), b.state_hash());
+        assert_eq!(a.chunk_hashes(16), b.chunk_hashes(16));
+        // 64 x 40 cells in 16 x 16 chunks
+        assert_eq!(a.chunk_hashes(16).len(), 4 * 3);
+        assert!(a.chunk_hashes(0).is_empty());
+
+        a.put_particle(40, 20, ParticleKind::Water);
+        b.put_particle(40, 20, ParticleKind::Salt);
+        assert_ne!(a.state_hash(), b.state_hash());
+        let differing: Vec<usize> = a.chunk_hashes(16).iter()
+            .zip(b.chunk_hashes(16))
+            .enumerate()
+            .filter(|(_, (ha, hb))| **ha != *hb)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(differing, vec![4 + 2]);
+
+        // per-particle state counts too, not just kind: water flowing left
+        // into a gap and water flowing right into it differ
+        b.put_particle(40, 20, ParticleKind::Water);
+        assert_eq!(a.state_hash(), b.state_hash());
+        for g in [&mut a, &mut b] {
+            g.put_particle(40, 20, ParticleKind::Background);
+            for x in 38..43 {
+                g.put_particle(x, 21, ParticleKind::Concrete);
+            }
+            g.put_particle(38, 20, ParticleKind::Concrete);
+            g.put_particle(42, 20, ParticleKind::Concrete);
+        }
+        a.put_particle(41, 20, ParticleKind::Water);
+        b.put_particle(39, 20, ParticleKind::Water);
+        a.step(1);
+        b.step(1);
+        assert_eq!(a.get_kind(40, 20), ParticleKind::Water);
+        assert_eq!(b.get_kind(40, 20), ParticleKind::Water);
+        assert_eq!(a.get_stats().counts(), b.get_stats().counts());
+        assert_ne!(a.state_hash(), b.state_hash());
+    }
+
+    #[test]
+    pub fn test_neighbours_at_edge() {
+        let mut g = Game::new();
+        g.put_particle(1, 0, ParticleKind::Water);
+        let n = g.get_neighbours(0, 0);
+        assert_eq!(n[1][2], ParticleKind::Water);
+        assert_eq!(n[0][0], ParticleKind::Background);
+    }
+
 }
\ No newline at end of file
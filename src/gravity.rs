@@ -0,0 +1,146 @@
+use std::f32::consts::FRAC_PI_4;
+
+use super::particles::particle::Neighbours;
+
+/// The eight directions around a cell, going round from straight down. Each
+/// is 45 degrees on from the one before.
+const RING: [(i32, i32); 8] = [
+    (0, 1), (-1, 1), (-1, 0), (-1, -1),
+    (0, -1), (1, -1), (1, 0), (1, 1)
+];
+
+/// The nearest of the eight directions around a cell to (`x`, `y`), or
+/// (0, 0) if that's zero.
+pub fn quantise(x: f32, y: f32) -> (i32, i32) {
+    if x == 0.0 && y == 0.0 {
+        return (0, 0);
+    }
+    let turns = (y.atan2(x) / FRAC_PI_4).round() as i32;
+    let angle = turns as f32 * FRAC_PI_4;
+    (angle.cos().round() as i32, angle.sin().round() as i32)
+}
+
+/// Which way is down, and how strongly things are pulled that way.
+///
+/// Particle rules are all written as if down were (0, 1). The game shows
+/// each particle its neighbours turned round so that down is the way gravity
+/// pulls, and turns the particle's action back again.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Gravity {
+    /// One of the eight directions around a cell, or (0, 0) for none.
+    pub direction: (i32, i32),
+    /// How hard things fall, relative to normal.
+    pub strength: f32
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity{direction: (0, 1), strength: 1.0}
+    }
+}
+
+impl Gravity {
+
+    /// Gravity pulling along (`x`, `y`), to the nearest of the eight
+    /// directions around a cell.
+    pub fn new(x: f32, y: f32, strength: f32) -> Gravity {
+        Gravity{direction: quantise(x, y), strength}
+    }
+
+    /// No gravity at all: nothing falls, flows or rises.
+    pub fn is_zero(&self) -> bool {
+        self.direction == (0, 0) || self.strength == 0.0
+    }
+
+    /// How many eighths of a turn the world is turned round from normal.
+    fn turns(&self) -> usize {
+        RING.iter().position(|d| *d == self.direction).unwrap_or(0)
+    }
+
+    /// The offset (`dx`, `dy`) to a neighbouring cell, as seen by a particle
+    /// which thinks down is (0, 1), turned round to which way it really is.
+    pub fn to_world(&self, dx: i32, dy: i32) -> (i32, i32) {
+        match RING.iter().position(|d| *d == (dx, dy)) {
+            Some(i) => RING[(i + self.turns()) % RING.len()],
+            None => (dx, dy)
+        }
+    }
+
+    /// `neighbours` turned round so that down is (0, 1).
+    pub fn to_frame(&self, neighbours: &Neighbours) -> Neighbours {
+        if self.turns() == 0 {
+            return *neighbours;
+        }
+        let mut turned = *neighbours;
+        for (dx, dy) in RING {
+            let (wx, wy) = self.to_world(dx, dy);
+            turned[(dy + 1) as usize][(dx + 1) as usize] = neighbours[(wy + 1) as usize][(wx + 1) as usize];
+        }
+        turned
+    }
+
+    /// Unit vector pointing down.
+    pub fn down(&self) -> (f32, f32) {
+        let (x, y) = (self.direction.0 as f32, self.direction.1 as f32);
+        let length = (x*x + y*y).sqrt();
+        if length == 0.0 { (0.0, 0.0) } else { (x / length, y / length) }
+    }
+
+    /// A velocity measured as usual, turned round so that y is along the
+    /// pull of gravity and x across it.
+    pub fn velocity_to_frame(&self, v: (f32, f32)) -> (f32, f32) {
+        let (gx, gy) = self.down();
+        (v.0*gy - v.1*gx, v.0*gx + v.1*gy)
+    }
+
+    /// The opposite of `velocity_to_frame`.
+    pub fn velocity_to_world(&self, v: (f32, f32)) -> (f32, f32) {
+        let (gx, gy) = self.down();
+        (v.0*gy + v.1*gx, -v.0*gx + v.1*gy)
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::kinds::ParticleKind;
+
+    #[test]
+    pub fn test_quantised() {
+        assert_eq!(Gravity::new(0.0, 1.0, 1.0).direction, (0, 1));
+        assert_eq!(Gravity::new(-3.0, 0.1, 1.0).direction, (-1, 0));
+        assert_eq!(Gravity::new(1.0, -1.2, 1.0).direction, (1, -1));
+        assert!(Gravity::new(0.0, 0.0, 1.0).is_zero());
+        assert!(Gravity::new(0.0, 1.0, 0.0).is_zero());
+    }
+
+    #[test]
+    pub fn test_turning() {
+        let left = Gravity::new(-1.0, 0.0, 1.0);
+        assert_eq!(left.to_world(0, 1), (-1, 0));
+        assert_eq!(left.to_world(1, 0), (0, 1));
+        assert_eq!(left.to_world(0, 0), (0, 0));
+
+        // sand to the left, as seen by something falling leftwards, is below
+        let mut n = [[ParticleKind::Background; 3]; 3];
+        n[1][0] = ParticleKind::Sand;
+        assert_eq!(left.to_frame(&n)[2][1], ParticleKind::Sand);
+
+        let normal = Gravity::default();
+        assert_eq!(normal.to_frame(&n), n);
+        assert_eq!(normal.to_world(1, 1), (1, 1));
+    }
+
+    #[test]
+    pub fn test_velocity_frames() {
+        let up = Gravity::new(0.0, -1.0, 1.0);
+        assert_eq!(up.velocity_to_frame((0.0, -2.0)), (0.0, 2.0));
+        for g in [Gravity::default(), up, Gravity::new(1.0, 1.0, 1.0)] {
+            let (x, y) = g.velocity_to_world(g.velocity_to_frame((1.5, -0.5)));
+            assert!((x - 1.5).abs() < 1e-6 && (y + 0.5).abs() < 1e-6);
+        }
+        assert_eq!(Gravity::default().velocity_to_frame((1.5, -0.5)), (1.5, -0.5));
+    }
+}
@@ -0,0 +1,57 @@
+/// 64-bit FNV-1a hasher.
+///
+/// Unlike `std`'s default hasher this is guaranteed to give the same result
+/// on every platform and compiler version, so hashes of the world can be
+/// stored and compared between builds and between peers.
+#[derive(Clone, Debug)]
+pub struct StableHasher {
+    state: u64
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StableHasher {
+
+    pub fn new() -> StableHasher {
+        StableHasher { state: FNV_OFFSET }
+    }
+
+    pub fn write_u8(&mut self, v: u8) {
+        self.state ^= v as u64;
+        self.state = self.state.wrapping_mul(FNV_PRIME);
+    }
+
+    pub fn write_u64(&mut self, v: u64) {
+        for b in v.to_le_bytes() {
+            self.write_u8(b);
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_known_values() {
+        // reference values for FNV-1a 64
+        assert_eq!(StableHasher::new().finish(), 0xcbf29ce484222325);
+        let mut h = StableHasher::new();
+        h.write_u8(b'a');
+        assert_eq!(h.finish(), 0xaf63dc4c8601ec8c);
+    }
+
+}
@@ -0,0 +1,99 @@
+use super::kinds::ParticleKind;
+
+/// Temperature of the world when left alone, in degrees C.
+pub const AMBIENT_TEMPERATURE: f32 = 20.0;
+
+/// Fraction of the difference from ambient temperature which empty space
+/// loses each tick, as heat escapes the world.
+pub const AIR_COOLING: f32 = 0.01;
+
+/// Most heat, as a fraction of the temperature difference times the smaller
+/// heat capacity, which may cross one boundary in a tick. A cell has four
+/// boundaries, so any more and a hot cell could swing past its neighbours'
+/// temperature and the whole grid would ring and blow up.
+pub const MAX_EXCHANGE: f32 = 0.125;
+
+/// Conduct heat between neighbouring cells for one tick.
+///
+/// `kinds` and `temperatures` describe a grid `n_cols` wide, row by row. Heat
+/// flows across each horizontal and vertical boundary in proportion to the
+/// temperature difference, and all flows are worked out from the
+/// temperatures at the start of the tick so the result doesn't depend on the
+/// order cells are visited in. Each boundary passes no more than
+/// `MAX_EXCHANGE` allows, however conductive the cells either side are.
+pub fn conduct(kinds: &[ParticleKind], temperatures: &mut [f32], n_cols: usize) {
+    let n_cells = temperatures.len();
+    let conductivity: Vec<f32> = kinds.iter().map(|k| k.conductivity()).collect();
+    let capacity: Vec<f32> = kinds.iter().map(|k| k.heat_capacity()).collect();
+    let mut flow = vec![0.0; n_cells];
+    let mut exchange = |a: usize, b: usize| {
+        let dt = temperatures[a] - temperatures[b];
+        // most of the world is usually sat at the same temperature
+        if dt != 0.0 {
+            let k = conductivity[a].min(conductivity[b])
+                .min(MAX_EXCHANGE * capacity[a].min(capacity[b]));
+            let q = k * dt;
+            flow[a] -= q;
+            flow[b] += q;
+        }
+    };
+    for idx in 0..n_cells {
+        if (idx + 1) % n_cols != 0 {
+            exchange(idx, idx + 1);
+        }
+        if idx + n_cols < n_cells {
+            exchange(idx, idx + n_cols);
+        }
+    }
+
+    for idx in 0..n_cells {
+        if flow[idx] != 0.0 {
+            temperatures[idx] += flow[idx] / capacity[idx];
+        }
+        if kinds[idx] == ParticleKind::Background && temperatures[idx] != AMBIENT_TEMPERATURE {
+            temperatures[idx] += AIR_COOLING * (AMBIENT_TEMPERATURE - temperatures[idx]);
+        }
+    }
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_heat_spreads_and_is_conserved() {
+        let kinds = [ParticleKind::Concrete; 9];
+        let mut t = [0.0; 9];
+        t[4] = 90.0;
+        for _ in 0..1000 {
+            conduct(&kinds, &mut t, 3);
+        }
+        for v in t {
+            assert!((v - 10.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    pub fn test_insulators_insulate() {
+        let kinds = [ParticleKind::Water, ParticleKind::Cornucopia, ParticleKind::Water];
+        let mut t = [100.0, 20.0, 20.0];
+        conduct(&kinds, &mut t, 3);
+        assert_eq!(t, [100.0, 20.0, 20.0]);
+    }
+
+    #[test]
+    pub fn test_conductors_stay_stable() {
+        let most_conductive = ParticleKind::ALL.iter().copied()
+            .max_by(|a, b| (a.conductivity() / a.heat_capacity()).total_cmp(&(b.conductivity() / b.heat_capacity())))
+            .unwrap();
+        let kinds = [most_conductive; 25];
+        // a checkerboard is the hardest pattern to smooth out stably
+        let mut t: Vec<f32> = (0..25).map(|i| if (i % 5 + i / 5) % 2 == 0 { 0.0 } else { 100.0 }).collect();
+        for _ in 0..1000 {
+            conduct(&kinds, &mut t, 5);
+            assert!(t.iter().all(|v| (0.0..=100.0).contains(v)), "{:?}", t);
+        }
+    }
+
+}
@@ -1,9 +1,324 @@
+use wasm_bindgen::prelude::*;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+use super::heat::AMBIENT_TEMPERATURE;
+use super::particles::fire::FLAME_TEMPERATURE;
+use super::particles::lava::{ERUPTION_TEMPERATURE, SOLIDIFY_TEMPERATURE, MELT_TEMPERATURE};
+
+/// Wires, heaters and switches any hotter than this melt down into lava.
+/// Well above the hottest a heater gets.
+pub const METAL_MELT_TEMPERATURE: f32 = 1085.0;
+
+#[wasm_bindgen]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum ParticleKind {
     Background,
     Salt,
     Water,
     Concrete,
-    Cornucopia
-}
\ No newline at end of file
+    Cornucopia,
+    Sand,
+    Brine,
+    Wood,
+    Fire,
+    Smoke,
+    Steam,
+    Ash,
+    Ice,
+    Lava,
+    Stone,
+    Oil,
+    Acid,
+    Glass,
+    Snow,
+    Gunpowder,
+    Tnt,
+    Soil,
+    Seed,
+    Plant,
+    Wire,
+    Battery,
+    Heater,
+    Lamp,
+    Switch,
+    Source,
+    Drain,
+    Life
+}
+
+/// State of matter of a kind of particle, which decides what it can move
+/// through.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Phase {
+    Empty,
+    Solid,
+    Powder,
+    Liquid,
+    Gas
+}
+
+impl ParticleKind {
+    /// Every kind of particle, in declaration order; a kind's position in
+    /// this list is `kind as usize`.
+    pub const ALL: [ParticleKind; 32] = [
+        ParticleKind::Background,
+        ParticleKind::Salt,
+        ParticleKind::Water,
+        ParticleKind::Concrete,
+        ParticleKind::Cornucopia,
+        ParticleKind::Sand,
+        ParticleKind::Brine,
+        ParticleKind::Wood,
+        ParticleKind::Fire,
+        ParticleKind::Smoke,
+        ParticleKind::Steam,
+        ParticleKind::Ash,
+        ParticleKind::Ice,
+        ParticleKind::Lava,
+        ParticleKind::Stone,
+        ParticleKind::Oil,
+        ParticleKind::Acid,
+        ParticleKind::Glass,
+        ParticleKind::Snow,
+        ParticleKind::Gunpowder,
+        ParticleKind::Tnt,
+        ParticleKind::Soil,
+        ParticleKind::Seed,
+        ParticleKind::Plant,
+        ParticleKind::Wire,
+        ParticleKind::Battery,
+        ParticleKind::Heater,
+        ParticleKind::Lamp,
+        ParticleKind::Switch,
+        ParticleKind::Source,
+        ParticleKind::Drain,
+        ParticleKind::Life
+    ];
+
+    /// Look up a kind by its name, as printed by `Debug`.
+    pub fn from_name(name: &str) -> Option<ParticleKind> {
+        ParticleKind::ALL.iter().copied().find(|k| format!("{:?}", k) == name)
+    }
+
+    pub fn phase(&self) -> Phase {
+        match self {
+            ParticleKind::Background => Phase::Empty,
+            ParticleKind::Salt => Phase::Powder,
+            ParticleKind::Water => Phase::Liquid,
+            ParticleKind::Concrete => Phase::Solid,
+            ParticleKind::Cornucopia => Phase::Solid,
+            ParticleKind::Sand => Phase::Powder,
+            ParticleKind::Brine => Phase::Liquid,
+            ParticleKind::Wood => Phase::Solid,
+            ParticleKind::Fire => Phase::Solid,
+            ParticleKind::Smoke => Phase::Gas,
+            ParticleKind::Steam => Phase::Gas,
+            ParticleKind::Ash => Phase::Powder,
+            ParticleKind::Ice => Phase::Solid,
+            ParticleKind::Lava => Phase::Liquid,
+            ParticleKind::Stone => Phase::Solid,
+            ParticleKind::Oil => Phase::Liquid,
+            ParticleKind::Acid => Phase::Liquid,
+            ParticleKind::Glass => Phase::Solid,
+            ParticleKind::Snow => Phase::Powder,
+            ParticleKind::Gunpowder => Phase::Powder,
+            ParticleKind::Tnt => Phase::Solid,
+            ParticleKind::Soil => Phase::Powder,
+            ParticleKind::Seed => Phase::Powder,
+            ParticleKind::Plant => Phase::Solid,
+            ParticleKind::Wire => Phase::Solid,
+            ParticleKind::Battery => Phase::Solid,
+            ParticleKind::Heater => Phase::Solid,
+            ParticleKind::Lamp => Phase::Solid,
+            ParticleKind::Switch => Phase::Solid,
+            ParticleKind::Source => Phase::Solid,
+            ParticleKind::Drain => Phase::Solid,
+            ParticleKind::Life => Phase::Solid
+        }
+    }
+
+    /// Density relative to water.
+    pub fn density(&self) -> f32 {
+        match self {
+            ParticleKind::Background => 0.0,
+            ParticleKind::Salt => 2.2,
+            ParticleKind::Water => 1.0,
+            ParticleKind::Concrete => 2.4,
+            ParticleKind::Cornucopia => 1.0,
+            ParticleKind::Sand => 1.6,
+            ParticleKind::Brine => 1.2,
+            ParticleKind::Wood => 0.7,
+            ParticleKind::Fire => 0.0,
+            ParticleKind::Smoke => 0.001,
+            ParticleKind::Steam => 0.0006,
+            ParticleKind::Ash => 0.6,
+            ParticleKind::Ice => 0.9,
+            ParticleKind::Lava => 2.6,
+            ParticleKind::Stone => 2.7,
+            ParticleKind::Oil => 0.8,
+            ParticleKind::Acid => 1.1,
+            ParticleKind::Glass => 2.5,
+            ParticleKind::Snow => 0.2,
+            ParticleKind::Gunpowder => 1.7,
+            ParticleKind::Tnt => 1.6,
+            ParticleKind::Soil => 1.5,
+            ParticleKind::Seed => 1.1,
+            ParticleKind::Plant => 0.8,
+            ParticleKind::Wire => 8.9,
+            ParticleKind::Battery => 3.0,
+            ParticleKind::Heater => 7.8,
+            ParticleKind::Lamp => 2.5,
+            ParticleKind::Switch => 8.9,
+            ParticleKind::Source => 1.0,
+            ParticleKind::Drain => 1.0,
+            ParticleKind::Life => 1.0
+        }
+    }
+
+    /// Chance per tick that a flame licking at a particle of this kind will
+    /// set it alight.
+    pub fn flammability(&self) -> f32 {
+        match self {
+            ParticleKind::Wood => 0.3,
+            ParticleKind::Oil => 0.6,
+            ParticleKind::Gunpowder => 0.9,
+            ParticleKind::Seed => 0.2,
+            ParticleKind::Plant => 0.2,
+            ParticleKind::Life => 0.5,
+            _ => 0.0
+        }
+    }
+
+    /// Chance that acid fails to eat into a particle of this kind, each time
+    /// it tries; 1 for inert kinds which acid can't touch.
+    pub fn acid_resistance(&self) -> f32 {
+        match self {
+            ParticleKind::Salt => 0.5,
+            ParticleKind::Concrete => 0.98,
+            ParticleKind::Sand => 0.8,
+            ParticleKind::Wood => 0.3,
+            ParticleKind::Ash => 0.4,
+            ParticleKind::Ice => 0.7,
+            ParticleKind::Snow => 0.5,
+            ParticleKind::Gunpowder => 0.5,
+            ParticleKind::Soil => 0.7,
+            ParticleKind::Seed => 0.4,
+            ParticleKind::Plant => 0.3,
+            ParticleKind::Stone => 0.95,
+            ParticleKind::Wire | ParticleKind::Switch => 0.8,
+            ParticleKind::Oil => 0.9,
+            _ => 1.0
+        }
+    }
+
+    /// How well a particle of this kind stands up to a blast, from 0 for
+    /// things any blast sweeps away to 1 for things no blast can break.
+    pub fn hardness(&self) -> f32 {
+        match self {
+            ParticleKind::Background | ParticleKind::Fire | ParticleKind::Smoke | ParticleKind::Steam => 0.0,
+            ParticleKind::Water | ParticleKind::Brine | ParticleKind::Oil | ParticleKind::Acid => 0.1,
+            ParticleKind::Ash | ParticleKind::Snow | ParticleKind::Gunpowder | ParticleKind::Seed
+                | ParticleKind::Life => 0.1,
+            ParticleKind::Salt | ParticleKind::Sand | ParticleKind::Lava | ParticleKind::Plant => 0.2,
+            ParticleKind::Soil => 0.25,
+            ParticleKind::Wood | ParticleKind::Ice | ParticleKind::Glass => 0.3,
+            ParticleKind::Concrete | ParticleKind::Stone => 0.8,
+            ParticleKind::Wire | ParticleKind::Battery | ParticleKind::Heater | ParticleKind::Lamp
+                | ParticleKind::Switch => 0.6,
+            ParticleKind::Cornucopia | ParticleKind::Tnt | ParticleKind::Source | ParticleKind::Drain => 1.0
+        }
+    }
+
+    /// How readily heat flows into and out of particles of this kind, as the
+    /// fraction of a temperature difference which flows across a boundary
+    /// per tick. Heat flows across a boundary at the rate of the worse
+    /// conductor of the two sides.
+    pub fn conductivity(&self) -> f32 {
+        match self {
+            ParticleKind::Background => 0.002,
+            ParticleKind::Salt => 0.05,
+            ParticleKind::Water => 0.1,
+            ParticleKind::Concrete => 0.02,
+            ParticleKind::Cornucopia => 0.0,
+            ParticleKind::Sand => 0.03,
+            ParticleKind::Brine => 0.1,
+            ParticleKind::Wood => 0.01,
+            ParticleKind::Fire => 0.2,
+            ParticleKind::Smoke => 0.002,
+            ParticleKind::Steam => 0.002,
+            ParticleKind::Ash => 0.02,
+            ParticleKind::Ice => 0.15,
+            ParticleKind::Lava => 0.05,
+            ParticleKind::Stone => 0.04,
+            ParticleKind::Oil => 0.03,
+            ParticleKind::Acid => 0.1,
+            ParticleKind::Glass => 0.01,
+            ParticleKind::Snow => 0.01,
+            ParticleKind::Gunpowder => 0.03,
+            ParticleKind::Tnt => 0.02,
+            ParticleKind::Soil => 0.03,
+            ParticleKind::Seed => 0.02,
+            ParticleKind::Plant => 0.02,
+            ParticleKind::Wire => 0.3,
+            ParticleKind::Battery => 0.02,
+            ParticleKind::Heater => 0.3,
+            ParticleKind::Lamp => 0.05,
+            ParticleKind::Switch => 0.3,
+            ParticleKind::Source => 0.0,
+            ParticleKind::Drain => 0.0,
+            ParticleKind::Life => 0.01
+        }
+    }
+
+    /// How much heat it takes to warm a particle of this kind by a degree,
+    /// relative to air.
+    pub fn heat_capacity(&self) -> f32 {
+        match self {
+            ParticleKind::Water | ParticleKind::Brine => 4.0,
+            ParticleKind::Wood | ParticleKind::Steam | ParticleKind::Ice | ParticleKind::Oil | ParticleKind::Snow
+                | ParticleKind::Plant => 2.0,
+            _ => 1.0
+        }
+    }
+
+    /// Temperature of a freshly made particle of this kind.
+    pub fn initial_temperature(&self) -> f32 {
+        match self {
+            ParticleKind::Fire => FLAME_TEMPERATURE,
+            ParticleKind::Steam => 110.0,
+            ParticleKind::Ice | ParticleKind::Snow => -10.0,
+            ParticleKind::Lava => ERUPTION_TEMPERATURE,
+            _ => AMBIENT_TEMPERATURE
+        }
+    }
+
+    /// What a particle of this kind turns into at the given temperature, if
+    /// it's too hot or too cold to stay as it is.
+    pub fn transition(&self, temperature: f32) -> Option<ParticleKind> {
+        match self {
+            ParticleKind::Water if temperature >= 100.0 => Some(ParticleKind::Steam),
+            ParticleKind::Water if temperature < 0.0 => Some(ParticleKind::Ice),
+            ParticleKind::Steam if temperature < 100.0 => Some(ParticleKind::Water),
+            ParticleKind::Ice if temperature > 0.0 => Some(ParticleKind::Water),
+            ParticleKind::Snow if temperature > 0.0 => Some(ParticleKind::Water),
+            ParticleKind::Wood | ParticleKind::Plant if temperature >= 300.0 => Some(ParticleKind::Fire),
+            ParticleKind::Oil if temperature >= 250.0 => Some(ParticleKind::Fire),
+            ParticleKind::Gunpowder if temperature >= 200.0 => Some(ParticleKind::Fire),
+            ParticleKind::Lava if temperature < SOLIDIFY_TEMPERATURE => Some(ParticleKind::Stone),
+            ParticleKind::Stone if temperature > MELT_TEMPERATURE => Some(ParticleKind::Lava),
+            ParticleKind::Wire | ParticleKind::Heater | ParticleKind::Switch
+                if temperature > METAL_MELT_TEMPERATURE => Some(ParticleKind::Lava),
+            _ => None
+        }
+    }
+
+    /// Whether a moving particle of this kind can take the place of `other`:
+    /// anything can move into empty space, and things sink through lighter
+    /// liquids and gases.
+    pub fn can_displace(&self, other: ParticleKind) -> bool {
+        match other.phase() {
+            Phase::Empty => true,
+            Phase::Liquid | Phase::Gas => self.density() > other.density(),
+            Phase::Solid | Phase::Powder => false
+        }
+    }
+}
@@ -12,9 +12,22 @@
 //! 
 
 pub mod actions;
+pub mod blast;
 pub mod colour;
+pub mod conflict;
+pub mod electricity;
 pub mod game;
+pub mod gravity;
+pub mod hash;
+pub mod heat;
 pub mod kinds;
+pub mod life;
 pub mod log;
+pub mod motion;
 pub mod point;
-pub mod particles;
\ No newline at end of file
+pub mod particles;
+pub mod recording;
+pub mod rng;
+pub mod stats;
+pub mod time;
+pub mod wind;
\ No newline at end of file
@@ -0,0 +1,204 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::kinds::ParticleKind;
+
+/// Conway's Game of Life.
+pub const CONWAY: &str = "B3/S23";
+/// Like Life, with replicators.
+pub const HIGHLIFE: &str = "B36/S23";
+/// Every live cell dies each generation; explosive.
+pub const SEEDS: &str = "B2/S";
+/// Cells take a generation to die, leaving trails behind moving patterns.
+pub const BRIANS_BRAIN: &str = "B2/S/C3";
+
+/// An outer-totalistic cellular automaton rule, written as `B…/S…`, giving
+/// the numbers of live neighbours for which a dead cell is born and a live
+/// cell survives, with an optional `/C…` giving the number of states as in
+/// the Generations rules: cells which don't survive then take `C - 2`
+/// generations to die, and don't count as live while they do. Rules with
+/// `B0` aren't allowed, since every empty cell would come alive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+    states: u8
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        CONWAY.parse().unwrap()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let counts = |part: &str, prefix: char| -> Result<[bool; 9], String> {
+            let digits = part.strip_prefix(prefix)
+                .or(part.strip_prefix(prefix.to_ascii_lowercase()))
+                .ok_or(format!("expected {} in rule {:?}", prefix, s))?;
+            let mut counts = [false; 9];
+            for c in digits.chars() {
+                match c.to_digit(10) {
+                    Some(n) if n <= 8 => counts[n as usize] = true,
+                    _ => return Err(format!("bad count {:?} in rule {:?}", c, s))
+                }
+            }
+            Ok(counts)
+        };
+
+        let parts: Vec<&str> = s.trim().split('/').collect();
+        let states = match parts.get(2) {
+            None => 2,
+            Some(c) => c.strip_prefix(['C', 'c'])
+                .and_then(|n| n.parse().ok())
+                .filter(|n| *n >= 2)
+                .ok_or(format!("bad number of states in rule {:?}", s))?
+        };
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(format!("malformed rule {:?}", s));
+        }
+        let birth = counts(parts[0], 'B')?;
+        if birth[0] {
+            return Err(format!("rule {:?} would bring every empty cell to life", s));
+        }
+        Ok(Rule {
+            birth,
+            survival: counts(parts[1], 'S')?,
+            states
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = |c: &[bool; 9]| (0..9).filter(|n| c[*n]).map(|n| n.to_string()).collect::<String>();
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))?;
+        if self.states != 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+/// A change to a cell made by a generation of life.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Change {
+    Born,
+    Died
+}
+
+/// Run one generation of `rule` on the cells of kind `kind`.
+///
+/// `kinds` and `ages` describe a grid `n_cols` wide, row by row, where a
+/// cell of kind `kind` is live if its age is 0 and dying otherwise.
+/// Every cell is worked out from the grid as it was at the start of the
+/// generation. The ages of cells which survive or start dying are updated
+/// in place, and births and deaths are returned for the caller to make;
+/// cells are only born into empty space.
+pub fn step(rule: &Rule, kind: ParticleKind, kinds: &[ParticleKind], ages: &mut [u8], n_cols: usize) -> Vec<(usize, Change)> {
+    let n_rows = kinds.len() / n_cols;
+    let live = |idx: usize| kinds[idx] == kind && ages[idx] == 0;
+    let mut live_neighbours = vec![0u8; kinds.len()];
+    for idx in (0..kinds.len()).filter(|idx| live(*idx)) {
+        let (x, y) = (idx % n_cols, idx / n_cols);
+        for ny in y.saturating_sub(1)..(y + 2).min(n_rows) {
+            for nx in x.saturating_sub(1)..(x + 2).min(n_cols) {
+                if (nx, ny) != (x, y) {
+                    live_neighbours[ny * n_cols + nx] += 1;
+                }
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    for idx in 0..kinds.len() {
+        let n = live_neighbours[idx] as usize;
+        if kinds[idx] == ParticleKind::Background {
+            if rule.birth[n] {
+                changes.push((idx, Change::Born));
+            }
+        }
+        else if kinds[idx] == kind {
+            if ages[idx] == 0 && rule.survival[n] {
+                continue;
+            }
+            ages[idx] += 1;
+            if ages[idx] >= rule.states - 1 {
+                changes.push((idx, Change::Died));
+            }
+        }
+    }
+    changes
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    const B: ParticleKind = ParticleKind::Background;
+    const L: ParticleKind = ParticleKind::Life;
+
+    /// Run `generations` of `rule` on a grid drawn as rows of `#` (live),
+    /// `+` (dying) and `.` (empty), returning the result drawn the same way.
+    fn run(rule: &str, grid: &[&str], generations: usize) -> Vec<String> {
+        let rule: Rule = rule.parse().unwrap();
+        let n_cols = grid[0].len();
+        let mut kinds: Vec<ParticleKind> = grid.iter().flat_map(|r| r.chars()).map(|c| if c == '.' { B } else { L }).collect();
+        let mut ages: Vec<u8> = grid.iter().flat_map(|r| r.chars()).map(|c| if c == '+' { 1 } else { 0 }).collect();
+        for _ in 0..generations {
+            for (idx, change) in step(&rule, L, &kinds, &mut ages, n_cols) {
+                kinds[idx] = if change == Change::Born { L } else { B };
+                ages[idx] = 0;
+            }
+        }
+        kinds.chunks(n_cols).zip(ages.chunks(n_cols)).map(|(k, a)| {
+            k.iter().zip(a).map(|(k, a)| match (k, a) { (&B, _) => '.', (_, 0) => '#', _ => '+' }).collect()
+        }).collect()
+    }
+
+    #[test]
+    pub fn test_parse_rules() {
+        for rule in [CONWAY, HIGHLIFE, SEEDS, BRIANS_BRAIN] {
+            assert_eq!(rule.parse::<Rule>().unwrap().to_string(), rule);
+        }
+        assert_eq!("b3/s23".parse::<Rule>().unwrap(), Rule::default());
+        assert!("B3".parse::<Rule>().is_err());
+        assert!("B9/S23".parse::<Rule>().is_err());
+        assert!("B3/S23/C1".parse::<Rule>().is_err());
+        assert!("S23/B3".parse::<Rule>().is_err());
+        assert!("B0/S".parse::<Rule>().is_err());
+        assert!("B03/S23".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    pub fn test_blinker() {
+        let vertical = [".....", "..#..", "..#..", "..#..", "....."];
+        let horizontal = [".....", ".....", ".###.", ".....", "....."];
+        assert_eq!(run(CONWAY, &vertical, 1), horizontal);
+        assert_eq!(run(CONWAY, &vertical, 2), vertical);
+    }
+
+    #[test]
+    pub fn test_glider() {
+        let glider = ["......", "..#...", "...#..", ".###..", "......", "......"];
+        let moved = ["......", "......", "...#..", "....#.", "..###.", "......"];
+        assert_eq!(run(CONWAY, &glider, 4), moved);
+    }
+
+    #[test]
+    pub fn test_seeds() {
+        assert_eq!(run(SEEDS, &["....", ".##.", "...."], 1), [".##.", "....", ".##."]);
+    }
+
+    #[test]
+    pub fn test_brians_brain() {
+        assert_eq!(run(BRIANS_BRAIN, &["....", ".##.", "...."], 1), [".##.", ".++.", ".##."]);
+        // the dying cells don't count, so new ones are born beside them
+        assert_eq!(run(BRIANS_BRAIN, &["....", ".##.", "...."], 2)[1], "#..#");
+    }
+
+}
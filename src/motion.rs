@@ -0,0 +1,94 @@
+/// Speed, in cells per tick, a falling particle gains each tick.
+pub const GRAVITY: f32 = 0.5;
+
+/// Fastest a particle can fall, in cells per tick.
+pub const TERMINAL_VELOCITY: f32 = 8.0;
+
+/// Fraction of its sideways speed a moving particle keeps from one tick to
+/// the next.
+pub const DRAG: f32 = 0.8;
+
+/// Slowest a particle can land and still bounce or splash.
+pub const IMPACT_SPEED: f32 = 2.0;
+
+/// Fraction of its speed a grain keeps, sideways, when it bounces.
+pub const BOUNCE: f32 = 0.3;
+
+/// Fraction of its speed a drop of liquid keeps, sideways, when it splashes.
+pub const SPLASH: f32 = 0.6;
+
+/// How far a particle which chose to step by `step` actually tries to go,
+/// given its velocity `v`: as far along each axis as its speed that way
+/// carries it, if that's further.
+pub fn reach(step: (i32, i32), v: (f32, f32)) -> (i32, i32) {
+    let axis = |d: i32, v: f32| {
+        let n = v.trunc() as i32;
+        if d == 0 || ((v > 0.0) == (d > 0) && n.abs() > d.abs()) { n } else { d }
+    };
+    (axis(step.0, v.0), axis(step.1, v.1))
+}
+
+/// The offsets of the cells on a straight line from where a particle is to
+/// `to`, nearest first, not including the cell it starts in.
+pub fn ray(to: (i32, i32)) -> Vec<(i32, i32)> {
+    let n = to.0.abs().max(to.1.abs());
+    (1..=n).map(|k| {
+        let t = k as f32 / n as f32;
+        ((to.0 as f32 * t).round() as i32, (to.1 as f32 * t).round() as i32)
+    }).collect()
+}
+
+/// Velocities after a particle of `density`, falling at `v`, lands on
+/// another. If what it landed on can move, `other` is its density and it's
+/// shoved the other way, taking a share of the speed. Liquids splash and
+/// grains bounce, off to the `sideways` (-1 or 1) side.
+///
+/// Velocities here are turned so that y is along the pull of gravity and x
+/// across it.
+pub fn impact(v: (f32, f32), density: f32, other: Option<f32>, liquid: bool, sideways: f32) -> ((f32, f32), Option<(f32, f32)>) {
+    let speed = v.1;
+    if speed < IMPACT_SPEED {
+        return ((v.0 * DRAG, 0.0), None);
+    }
+    let keep = if liquid { SPLASH } else { BOUNCE };
+    let shoved = other.map(|d| (-sideways * speed * density / (density + d), 0.0));
+    ((sideways * speed * keep, 0.0), shoved)
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_reach() {
+        assert_eq!(reach((0, 1), (0.0, 0.5)), (0, 1));
+        assert_eq!(reach((0, 1), (0.0, 3.5)), (0, 3));
+        assert_eq!(reach((1, 1), (2.5, 2.0)), (2, 2));
+        assert_eq!(reach((0, 1), (-1.5, 0.0)), (-1, 1));
+        // sideways speed the other way doesn't help
+        assert_eq!(reach((-1, 0), (2.5, 0.0)), (-1, 0));
+        assert_eq!(reach((-1, 0), (-2.5, 0.0)), (-2, 0));
+    }
+
+    #[test]
+    pub fn test_ray() {
+        assert_eq!(ray((0, 3)), vec![(0, 1), (0, 2), (0, 3)]);
+        assert_eq!(ray((2, 2)), vec![(1, 1), (2, 2)]);
+        assert_eq!(ray((1, 4)), vec![(0, 1), (1, 2), (1, 3), (1, 4)]);
+        assert_eq!(ray((-2, 0)), vec![(-1, 0), (-2, 0)]);
+    }
+
+    #[test]
+    pub fn test_impact() {
+        // a gentle landing
+        assert_eq!(impact((1.0, 1.0), 1.0, Some(1.0), true, 1.0), ((0.8, 0.0), None));
+        // water splashes into water, shoving it the other way
+        assert_eq!(impact((0.0, 4.0), 1.0, Some(1.0), true, 1.0), ((2.4, 0.0), Some((-2.0, 0.0))));
+        // sand bounces off concrete
+        let (v, shoved) = impact((0.0, 4.0), 1.6, None, false, -1.0);
+        assert!((v.0 + 1.2).abs() < 1e-6);
+        assert!(shoved.is_none());
+    }
+}
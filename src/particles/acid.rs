@@ -0,0 +1,118 @@
+use super::particle::{Particle, Neighbours, Processable, Context, NEIGHBOUR_OFFSETS};
+use super::liquid;
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, ACID};
+
+/// How many particles a cell of acid can eat through before it is used up.
+pub const STRENGTH: u8 = 4;
+
+/// A liquid which eats into its neighbours, at a rate set by their
+/// `acid_resistance`, giving off smoke as it does.
+#[derive(Copy, Clone)]
+pub struct AcidParticle {
+    processed: bool,
+    last_dir_x: i32,
+    strength: u8
+}
+
+impl AcidParticle {
+
+    pub fn new() -> AcidParticle {
+        AcidParticle {
+            processed: false,
+            last_dir_x: 1,
+            strength: STRENGTH
+        }
+    }
+
+    pub fn get_strength(&self) -> u8 {
+        self.strength
+    }
+
+}
+
+impl Default for AcidParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for AcidParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        // start from a random neighbour, so that acid doesn't eat faster in
+        // any one direction
+        let start = ctx.rng.below(8) as usize;
+        for i in 0..8 {
+            let (x, y) = NEIGHBOUR_OFFSETS[(start + i) % 8];
+            let target = neighbours[(y + 1) as usize][(x + 1) as usize];
+            if ctx.rng.chance(1.0 - target.acid_resistance()) {
+                self.strength -= 1;
+                let this = if self.strength == 0 { ParticleKind::Background } else { ParticleKind::Acid };
+                return Action::React{x, y, this, that: ParticleKind::Smoke};
+            }
+        }
+
+        liquid::flow(ParticleKind::Acid, &mut self.last_dir_x, &neighbours)
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Acid
+    }
+    fn get_colour(&self) -> Colour {
+        ACID
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        ((self.strength as u64) << 32) | (self.last_dir_x as u32 as u64)
+    }
+}
+
+impl Processable for AcidParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    pub fn test_acid_is_used_up() {
+        let mut rng = Rng::new(38);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = AcidParticle::new();
+        let n = [[ParticleKind::Salt; 3]; 3];
+        for _ in 1..STRENGTH {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::React{this: ParticleKind::Acid, that: ParticleKind::Smoke, ..}));
+        }
+        assert!(matches!(p.get_action(n, &mut ctx), Action::React{this: ParticleKind::Background, that: ParticleKind::Smoke, ..}));
+        assert_eq!(p.get_strength(), 0);
+    }
+
+    #[test]
+    pub fn test_glass_is_immune() {
+        let mut rng = Rng::new(38);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = AcidParticle::new();
+        let mut n = [[ParticleKind::Glass; 3]; 3];
+        n[1][1] = ParticleKind::Acid;
+        for _ in 0..1000 {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::StayPut));
+        }
+        assert_eq!(p.get_strength(), STRENGTH);
+    }
+
+}
@@ -0,0 +1,51 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use super::powder;
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, ASH};
+
+/// What's left after a fire: a light powder which piles up loosely, and
+/// floats on water.
+#[derive(Copy, Clone, Default)]
+pub struct AshParticle {
+    processed: bool
+}
+
+impl AshParticle {
+
+    pub fn new() -> AshParticle {
+        AshParticle {
+            processed: false
+        }
+    }
+
+}
+
+impl Particle for AshParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        powder::fall(ParticleKind::Ash, &neighbours, ctx.rng)
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Ash
+    }
+    fn get_colour(&self) -> Colour {
+        ASH
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+}
+
+impl Processable for AshParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
@@ -1,4 +1,4 @@
-use super::particle::{Particle, Neighbours, Processable};
+use super::particle::{Particle, Neighbours, Processable, Context};
 use crate::actions::Action;
 use crate::kinds::ParticleKind;
 use crate::colour::{Colour, BLACK};
@@ -7,7 +7,7 @@ use crate::colour::{Colour, BLACK};
 pub struct Background;
 
 impl Particle for Background {
-    fn get_action(&mut self, neighbours: Neighbours) -> Action {
+    fn get_action(&mut self, _: Neighbours, _: &mut Context) -> Action {
         Action::StayPut
     }
     fn get_type(&self) -> ParticleKind {
@@ -0,0 +1,38 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, BATTERY};
+
+/// A source of electricity, which sends a steady train of pulses along any
+/// wire touching it.
+#[derive(Copy, Clone)]
+pub struct BatteryParticle;
+
+impl Particle for BatteryParticle {
+    fn get_action(&mut self, _: Neighbours, _: &mut Context) -> Action {
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Battery
+    }
+    fn get_colour(&self) -> Colour {
+        BATTERY
+    }
+    fn tick(&mut self){
+        // do nothing
+    }
+}
+
+impl Processable for BatteryParticle {
+    fn get_was_processed(&self) -> bool {
+        true
+    }
+
+    fn set_processed(&mut self) {
+        // do nothing
+    }
+
+    fn set_not_processed(&mut self) {
+        // do nothing
+    }
+}
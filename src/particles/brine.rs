@@ -0,0 +1,165 @@
+use super::particle::{Particle, Neighbours, Processable, Context, find_neighbour};
+use super::liquid;
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, BRINE};
+
+/// Most salt a cell of brine can hold, counted in grains.
+pub const SATURATION: u8 = 4;
+
+/// Chance per tick that brine open to the air above evaporates, leaving its
+/// salt behind as crystals.
+pub const EVAPORATION_CHANCE: f32 = 0.002;
+
+/// Brine this hot boils dry, leaving its salt behind.
+pub const BOILING_TEMPERATURE: f32 = 100.0;
+
+/// Salty water: a liquid denser than water, which carries on dissolving salt
+/// until saturated.
+#[derive(Copy, Clone)]
+pub struct BrineParticle {
+    processed: bool,
+    last_dir_x: i32,
+    salinity: u8
+}
+
+impl BrineParticle {
+
+    /// Brine made by dissolving a single grain of salt.
+    pub fn new() -> BrineParticle {
+        BrineParticle::with_salinity(1)
+    }
+
+    /// Brine holding `salinity` grains of salt, up to `SATURATION`.
+    pub fn with_salinity(salinity: u8) -> BrineParticle {
+        BrineParticle{
+            processed: false,
+            last_dir_x: 1,
+            salinity: salinity.clamp(1, SATURATION)
+        }
+    }
+
+    pub fn get_salinity(&self) -> u8 {
+        self.salinity
+    }
+
+    /// Drop a grain of salt as a crystal into the empty neighbouring cell at
+    /// `to`, if there is one, as the water holding it dries up. The last
+    /// grain is left where the brine was.
+    fn crystallise(&mut self, to: Option<(i32, i32)>) -> Option<Action> {
+        if self.salinity <= 1 {
+            return Some(Action::Become(ParticleKind::Salt));
+        }
+        let (x, y) = to?;
+        self.salinity -= 1;
+        Some(Action::React{x, y, this: ParticleKind::Brine, that: ParticleKind::Salt})
+    }
+
+}
+
+impl Default for BrineParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for BrineParticle {
+
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        if ctx.temperature >= BOILING_TEMPERATURE {
+            if let Some(action) = self.crystallise(find_neighbour(&neighbours, ParticleKind::Background)) {
+                return action;
+            }
+        }
+        else if self.salinity < SATURATION {
+            if let Some((x, y)) = find_neighbour(&neighbours, ParticleKind::Salt) {
+                self.salinity += 1;
+                return Action::React{x, y, this: ParticleKind::Brine, that: ParticleKind::Background};
+            }
+        }
+
+        if neighbours[0][1] == ParticleKind::Background && ctx.rng.chance(EVAPORATION_CHANCE) {
+            if let Some(action) = self.crystallise(Some((0, -1))) {
+                return action;
+            }
+        }
+
+        liquid::flow(ParticleKind::Brine, &mut self.last_dir_x, &neighbours)
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Brine
+    }
+    fn get_colour(&self) -> Colour {
+        BRINE
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        ((self.salinity as u64) << 32) | (self.last_dir_x as u32 as u64)
+    }
+}
+
+impl Processable for BrineParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    const B: ParticleKind = ParticleKind::Background;
+    const C: ParticleKind = ParticleKind::Concrete;
+    const S: ParticleKind = ParticleKind::Salt;
+    const W: ParticleKind = ParticleKind::Water;
+
+    #[test]
+    pub fn test_saturates() {
+        let mut rng = Rng::new(0);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = BrineParticle::new();
+        let n = [[C, C, C], [C, B, S], [C, C, C]];
+        for _ in 1..SATURATION {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::React{x: 1, y: 0, ..}));
+        }
+        assert_eq!(p.get_salinity(), SATURATION);
+        assert!(matches!(p.get_action(n, &mut ctx), Action::StayPut));
+    }
+
+    #[test]
+    pub fn test_boils_dry() {
+        let mut rng = Rng::new(0);
+        let mut ctx = Context::new(&mut rng);
+        ctx.temperature = 150.0;
+        let mut p = BrineParticle::with_salinity(SATURATION);
+        let n = [[C, C, C], [C, B, B], [C, C, C]];
+        for _ in 1..SATURATION {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::React{x: 1, y: 0, that: ParticleKind::Salt, ..}));
+        }
+        assert_eq!(p.get_salinity(), 1);
+        assert!(matches!(p.get_action(n, &mut ctx), Action::Become(ParticleKind::Salt)));
+    }
+
+    #[test]
+    pub fn test_sinks_through_water() {
+        let mut rng = Rng::new(0);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = BrineParticle::new();
+        let n = [[C, C, C], [C, B, C], [C, W, C]];
+        assert!(matches!(p.get_action(n, &mut ctx), Action::SwapWith{x: 0, y: 1}));
+    }
+
+}
@@ -1,74 +1,126 @@
-use super::particle::{Particle, Neighbours, Processable};
+use super::particle::{Particle, Neighbours, Processable, Context, NEIGHBOUR_OFFSETS};
 use crate::actions::Action;
 use crate::kinds::ParticleKind;
 use crate::colour::{Colour, GREEN};
 
+/// How a cornucopia behaves, chosen when it is painted.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Settings {
+    /// What to emit; `Background` to copy whatever touches it first.
+    pub kind: ParticleKind,
+    /// Chance per tick of emitting a particle.
+    pub rate: f32,
+    /// Offset of the neighbour to emit into when it's free, before trying
+    /// the others; (0, 0) for no preference.
+    pub direction: (i32, i32),
+    /// Kind of particle which, by touching the cornucopia, makes it forget
+    /// what it has copied and go back to its settings; `Background` for
+    /// none.
+    pub reset_by: ParticleKind
+}
+
+impl Settings {
+
+    pub fn new(kind: ParticleKind, rate: f32, direction: (i32, i32), reset_by: ParticleKind) -> Settings {
+        Settings {
+            kind,
+            rate: rate.clamp(0.0, 1.0),
+            direction: (direction.0.clamp(-1, 1), direction.1.clamp(-1, 1)),
+            reset_by
+        }
+    }
+
+}
+
+/// Copies whatever first touches it, emitting it every tick in any
+/// direction, and never forgets it.
+impl Default for Settings {
+    fn default() -> Self {
+        Settings::new(ParticleKind::Background, 1.0, (0, 0), ParticleKind::Background)
+    }
+}
+
+/// An endless supply of particles, of a kind either set when painted or
+/// copied from whatever touches it first.
 #[derive(Copy, Clone)]
 pub struct CornucopiaParticle {
+    processed: bool,
+    settings: Settings,
     particle_kind_to_create: ParticleKind
 }
 
-struct Pt(usize, usize);
-
 impl CornucopiaParticle {
 
     pub fn new() -> Self {
+        CornucopiaParticle::with_settings(Settings::default())
+    }
+
+    pub fn with_settings(settings: Settings) -> Self {
         CornucopiaParticle {
-            particle_kind_to_create: ParticleKind::Background
+            processed: false,
+            settings,
+            particle_kind_to_create: settings.kind
         }
     }
 
-    fn maybe_find_new_kind(&mut self, neighbours: &Neighbours) {
-        if self.particle_kind_to_create == ParticleKind::Background {
-            self.find_new_kind(neighbours);
-        }
+    pub fn get_settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// What the cornucopia is emitting at the moment, `Background` if
+    /// nothing yet.
+    pub fn get_kind_to_create(&self) -> ParticleKind {
+        self.particle_kind_to_create
     }
 
     fn find_new_kind(&mut self, neighbours: &Neighbours) {
-        for i in 0..3 {
-            for j in 0..3 {
-                if i == j && i == 1 {
-                    continue
-                }
-
-                if neighbours[i][j] != ParticleKind::Background {
-                    self.particle_kind_to_create = neighbours[i][j];
-                    return ();
-                }
+        for (x, y) in NEIGHBOUR_OFFSETS {
+            let kind = neighbours[(y + 1) as usize][(x + 1) as usize];
+            // copying another cornucopia would just copy its copies, and
+            // copying what resets it would be forgotten straight away
+            if kind != ParticleKind::Background && kind != ParticleKind::Cornucopia && kind != self.settings.reset_by {
+                self.particle_kind_to_create = kind;
+                return;
             }
         }
     }
 
-    fn find_free_neighbour(neighbours: &Neighbours) -> Option<Pt> {
-        for i in 0..3 {
-            for j in 0..3 {
-                if i == j && i == 1 {
-                    continue
-                }
-
-                if neighbours[i][j] == ParticleKind::Background {
-                    return Some(Pt(i, j));
-                }
-            }
+    fn find_free_neighbour(&self, neighbours: &Neighbours, ctx: &mut Context) -> Option<(i32, i32)> {
+        let is_free = |(x, y): (i32, i32)| neighbours[(y + 1) as usize][(x + 1) as usize] == ParticleKind::Background;
+        if self.settings.direction != (0, 0) && is_free(self.settings.direction) {
+            return Some(self.settings.direction);
         }
+        let start = ctx.rng.below(8) as usize;
+        (0..8).map(|i| NEIGHBOUR_OFFSETS[(start + i) % 8]).find(|&offset| is_free(offset))
+    }
+}
 
-        return None;
+impl Default for CornucopiaParticle {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Particle for CornucopiaParticle {
-    fn get_action(&mut self, neighbours: Neighbours) -> Action {
-        self.maybe_find_new_kind(&neighbours);
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        let reset_by = self.settings.reset_by;
+        // the middle of `neighbours` is this cornucopia itself
+        let touching = neighbours.iter().flatten().enumerate().any(|(i, k)| i != 4 && *k == reset_by);
+        if reset_by != ParticleKind::Background && touching {
+            self.particle_kind_to_create = self.settings.kind;
+        }
 
-        if self.particle_kind_to_create != ParticleKind::Background {
+        if self.particle_kind_to_create == ParticleKind::Background {
+            self.find_new_kind(&neighbours);
+        }
 
-            match Self::find_free_neighbour(&neighbours) {
-                Some(Pt(x, y)) => Action::GrowInto{x: x as i32 - 1, y: y as i32 - 1, kind:self.particle_kind_to_create},
-                None => Action::StayPut
-            }
+        if self.particle_kind_to_create == ParticleKind::Background || !ctx.rng.chance(self.settings.rate) {
+            return Action::StayPut;
         }
-        else {
-            Action::StayPut
+
+        match self.find_free_neighbour(&neighbours, ctx) {
+            Some((x, y)) => Action::GrowInto{x, y, kind: self.particle_kind_to_create},
+            None => Action::StayPut
         }
     }
     fn get_type(&self) -> ParticleKind {
@@ -80,18 +132,103 @@ impl Particle for CornucopiaParticle {
     fn tick(&mut self){
         self.set_not_processed()
     }
+    fn get_state(&self) -> u64 {
+        let s = &self.settings;
+        let direction = ((s.direction.0 + 1) * 3 + s.direction.1 + 1) as u64;
+        (self.particle_kind_to_create as u64)
+            | (s.kind as u64) << 8
+            | (s.reset_by as u64) << 16
+            | direction << 24
+            | (s.rate.to_bits() as u64) << 32
+    }
 }
 
 impl Processable for CornucopiaParticle {
     fn get_was_processed(&self) -> bool {
-        false
+        self.processed
     }
 
     fn set_processed(&mut self) {
-        // do nothing
+        self.processed = true;
     }
 
     fn set_not_processed(&mut self) {
-        // do nothing
+        self.processed = false
+    }
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    const B: ParticleKind = ParticleKind::Background;
+
+    #[test]
+    pub fn test_copies_first_touch() {
+        let mut rng = Rng::new(44);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = CornucopiaParticle::new();
+        assert!(matches!(p.get_action([[B; 3]; 3], &mut ctx), Action::StayPut));
+
+        let mut n = [[B; 3]; 3];
+        n[0][1] = ParticleKind::Sand;
+        assert!(matches!(p.get_action(n, &mut ctx), Action::GrowInto{kind: ParticleKind::Sand, ..}));
+        n[0][1] = ParticleKind::Water;
+        assert!(matches!(p.get_action(n, &mut ctx), Action::GrowInto{kind: ParticleKind::Sand, ..}));
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn test_settings() {
+        let mut rng = Rng::new(44);
+        let mut ctx = Context::new(&mut rng);
+        let settings = Settings::new(ParticleKind::Water, 1.0, (1, 0), ParticleKind::Fire);
+        let mut p = CornucopiaParticle::with_settings(settings);
+        let mut n = [[B; 3]; 3];
+        n[0][1] = ParticleKind::Sand;
+        for _ in 0..10 {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::GrowInto{x: 1, y: 0, kind: ParticleKind::Water}));
+        }
+
+        // with the preferred cell taken, it goes elsewhere
+        n[1][2] = ParticleKind::Water;
+        assert!(matches!(p.get_action(n, &mut ctx), Action::GrowInto{kind: ParticleKind::Water, ..}));
+
+        // a slow one emits less
+        let mut p = CornucopiaParticle::with_settings(Settings::new(ParticleKind::Water, 0.1, (0, 0), B));
+        let emitted = (0..1000).filter(|_| matches!(p.get_action(n, &mut ctx), Action::GrowInto{..})).count();
+        assert!((50..150).contains(&emitted));
+    }
+
+    #[test]
+    pub fn test_reset() {
+        let mut rng = Rng::new(44);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = CornucopiaParticle::with_settings(Settings::new(B, 1.0, (0, 1), ParticleKind::Fire));
+        let mut n = [[B; 3]; 3];
+        n[0][1] = ParticleKind::Sand;
+        p.get_action(n, &mut ctx);
+        assert_eq!(p.get_kind_to_create(), ParticleKind::Sand);
+
+        // forgets the sand, and picks up something else instead
+        n[0][1] = ParticleKind::Fire;
+        n[1][0] = ParticleKind::Oil;
+        p.get_action(n, &mut ctx);
+        assert_eq!(p.get_kind_to_create(), ParticleKind::Oil);
+        n[0][1] = ParticleKind::Sand;
+        p.get_action(n, &mut ctx);
+        assert_eq!(p.get_kind_to_create(), ParticleKind::Oil);
+
+        // it doesn't reset itself
+        let mut p = CornucopiaParticle::with_settings(Settings::new(B, 1.0, (0, 1), ParticleKind::Cornucopia));
+        let mut n = [[B; 3]; 3];
+        n[1][1] = ParticleKind::Cornucopia;
+        n[0][1] = ParticleKind::Sand;
+        p.get_action(n, &mut ctx);
+        n[0][1] = B;
+        p.get_action(n, &mut ctx);
+        assert_eq!(p.get_kind_to_create(), ParticleKind::Sand);
+    }
+
+}
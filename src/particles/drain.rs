@@ -0,0 +1,58 @@
+use super::particle::{Particle, Neighbours, Processable, Context, NEIGHBOUR_OFFSETS};
+use crate::actions::Action;
+use crate::kinds::{ParticleKind, Phase};
+use crate::colour::{Colour, DRAIN};
+
+/// A plughole, which swallows whatever powder, liquid or gas touches it, one
+/// neighbour per tick. Solids, drains included, are left alone.
+#[derive(Copy, Clone, Default)]
+pub struct DrainParticle {
+    processed: bool
+}
+
+impl DrainParticle {
+
+    pub fn new() -> DrainParticle {
+        DrainParticle {
+            processed: false
+        }
+    }
+
+}
+
+impl Particle for DrainParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        let start = ctx.rng.below(8) as usize;
+        for i in 0..8 {
+            let (x, y) = NEIGHBOUR_OFFSETS[(start + i) % 8];
+            let target = neighbours[(y + 1) as usize][(x + 1) as usize];
+            if matches!(target.phase(), Phase::Powder | Phase::Liquid | Phase::Gas) {
+                return Action::React{x, y, this: ParticleKind::Drain, that: ParticleKind::Background};
+            }
+        }
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Drain
+    }
+    fn get_colour(&self) -> Colour {
+        DRAIN
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+}
+
+impl Processable for DrainParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
@@ -0,0 +1,114 @@
+use super::particle::{Particle, Neighbours, Processable, Context, find_neighbour, NEIGHBOUR_OFFSETS};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, RED, ORANGE, YELLOW};
+use crate::rng::Rng;
+
+/// Temperature a flame keeps itself at while it burns.
+pub const FLAME_TEMPERATURE: f32 = 600.0;
+
+/// Longest a flame can burn for, in ticks.
+pub const LIFETIME: u32 = 40;
+
+/// Chance per tick that a flame goes out before reaching its lifetime.
+pub const BURN_OUT_CHANCE: f32 = 0.03;
+
+/// Chance that a flame going out leaves ash behind, rather than smoke.
+pub const ASH_CHANCE: f32 = 0.3;
+
+/// Lick at the neighbours, which might catch, giving the action which sets
+/// one alight if any do. Starts from a random neighbour so that fire doesn't
+/// spread faster in any one direction.
+pub fn ignite(neighbours: &Neighbours, rng: &mut Rng) -> Option<Action> {
+    let start = rng.below(8) as usize;
+    for i in 0..8 {
+        let (x, y) = NEIGHBOUR_OFFSETS[(start + i) % 8];
+        let target = neighbours[(y + 1) as usize][(x + 1) as usize];
+        if rng.chance(target.flammability()) {
+            return Some(Action::GrowInto{x, y, kind: ParticleKind::Fire});
+        }
+    }
+    None
+}
+
+#[derive(Copy, Clone)]
+pub struct FireParticle {
+    processed: bool,
+    age: u32
+}
+
+impl FireParticle {
+
+    pub fn new() -> FireParticle {
+        FireParticle {
+            processed: false,
+            age: 0
+        }
+    }
+
+}
+
+impl Default for FireParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for FireParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        // put out by water, which boils away
+        if let Some((x, y)) = find_neighbour(&neighbours, ParticleKind::Water) {
+            return Action::React{x, y, this: ParticleKind::Background, that: ParticleKind::Steam};
+        }
+
+        self.age += 1;
+        ctx.temperature = ctx.temperature.max(FLAME_TEMPERATURE);
+
+        if let Some(action) = ignite(&neighbours, ctx.rng) {
+            return action;
+        }
+
+        if self.age >= LIFETIME || ctx.rng.chance(BURN_OUT_CHANCE) {
+            if ctx.rng.chance(ASH_CHANCE) {
+                Action::Become(ParticleKind::Ash)
+            }
+            else {
+                Action::Become(ParticleKind::Smoke)
+            }
+        }
+        else {
+            Action::StayPut
+        }
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Fire
+    }
+    fn get_colour(&self) -> Colour {
+        // flicker
+        match self.age % 3 {
+            0 => YELLOW,
+            1 => ORANGE,
+            _ => RED
+        }
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        self.age as u64
+    }
+}
+
+impl Processable for FireParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
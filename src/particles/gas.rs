@@ -0,0 +1,175 @@
+use super::particle::{Particle, Neighbours, Processable, Context, flip_vertically};
+use super::liquid;
+use crate::actions::Action;
+use crate::kinds::{ParticleKind, Phase};
+use crate::colour::{Colour, SMOKE, STEAM};
+use crate::rng::Rng;
+
+/// Ticks that smoke lingers before dissipating.
+pub const SMOKE_LIFETIME: u32 = 120;
+
+/// Ticks that steam lingers before cooling and condensing.
+pub const STEAM_LIFETIME: u32 = 240;
+
+/// Chance per tick that a gas rises, rather than drifting about.
+pub const RISE_CHANCE: f32 = 0.6;
+
+/// Chance per tick that steam pressed against a ceiling condenses.
+pub const CONDENSE_CHANCE: f32 = 0.02;
+
+/// A gas (smoke or steam) which rises and billows out under ceilings, the way
+/// a liquid falls and spreads over floors, until it dissipates.
+#[derive(Copy, Clone)]
+pub struct GasParticle {
+    processed: bool,
+    kind: ParticleKind,
+    last_dir_x: i32,
+    age: u32,
+    lifetime: u32
+}
+
+impl GasParticle {
+
+    pub fn smoke() -> GasParticle {
+        GasParticle::new(ParticleKind::Smoke, SMOKE_LIFETIME)
+    }
+
+    pub fn steam() -> GasParticle {
+        GasParticle::new(ParticleKind::Steam, STEAM_LIFETIME)
+    }
+
+    fn new(kind: ParticleKind, lifetime: u32) -> GasParticle {
+        GasParticle {
+            processed: false,
+            kind,
+            last_dir_x: 1,
+            age: 0,
+            lifetime
+        }
+    }
+
+    /// What the gas turns into once it has had its time.
+    fn dissipate(&self) -> Action {
+        match self.kind {
+            ParticleKind::Steam => Action::Become(ParticleKind::Water),
+            _ => Action::Pop
+        }
+    }
+
+    fn rise(&mut self, neighbours: &Neighbours) -> Action {
+        // gases only rise into empty space: anything heavier sinks through
+        // them under its own rules
+        let upside_down = flip_vertically(neighbours);
+        liquid::flow_with(&mut self.last_dir_x, &upside_down, |target, x, y| {
+            if target == ParticleKind::Background { Some(Action::MoveInto{x, y}) } else { None }
+        }).flipped_vertically()
+    }
+
+    fn drift(&self, neighbours: &Neighbours, rng: &mut Rng) -> Action {
+        let dx = if rng.chance(0.5) { -1 } else { 1 };
+        let dy = rng.below(3) as i32 - 1;
+        if neighbours[(dy + 1) as usize][(dx + 1) as usize] == ParticleKind::Background {
+            Action::MoveInto{x: dx, y: dy}
+        }
+        else {
+            Action::StayPut
+        }
+    }
+
+}
+
+impl Particle for GasParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        self.age += 1;
+        if self.age >= self.lifetime {
+            return self.dissipate();
+        }
+
+        let above = neighbours[0][1];
+        let against_ceiling = above != ParticleKind::Background && above.phase() != Phase::Gas;
+        if self.kind == ParticleKind::Steam && against_ceiling && ctx.rng.chance(CONDENSE_CHANCE) {
+            return self.dissipate();
+        }
+
+        if ctx.rng.chance(RISE_CHANCE) {
+            self.rise(&neighbours)
+        }
+        else {
+            self.drift(&neighbours, ctx.rng)
+        }
+    }
+    fn get_type(&self) -> ParticleKind {
+        self.kind
+    }
+    fn get_colour(&self) -> Colour {
+        match self.kind {
+            ParticleKind::Steam => STEAM,
+            _ => SMOKE
+        }
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        ((self.age as u64) << 32) | (self.last_dir_x as u32 as u64)
+    }
+}
+
+impl Processable for GasParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    const B: ParticleKind = ParticleKind::Background;
+    const C: ParticleKind = ParticleKind::Concrete;
+
+    #[test]
+    pub fn test_rises_like_upside_down_water() {
+        let mut rng = Rng::new(0);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = GasParticle::smoke();
+        let open = [[B; 3]; 3];
+        assert!(matches!(p.rise(&open), Action::MoveInto{x: 0, y: -1}));
+        let ceiling = [[C, C, B], [B, B, B], [B, B, B]];
+        assert!(matches!(p.rise(&ceiling), Action::MoveInto{x: 1, y: -1}));
+        let sealed = [[C, C, C], [C, B, B], [B, B, B]];
+        assert!(matches!(p.rise(&sealed), Action::MoveInto{x: 1, y: 0}));
+        for _ in 0..SMOKE_LIFETIME - 1 {
+            p.get_action(open, &mut ctx);
+        }
+        assert!(matches!(p.get_action(open, &mut ctx), Action::Pop));
+    }
+
+    #[test]
+    pub fn test_steam_condenses() {
+        let mut rng = Rng::new(0);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = GasParticle::steam();
+        let ceiling = [[C; 3], [C, B, C], [C; 3]];
+        let mut condensed = false;
+        for _ in 0..STEAM_LIFETIME {
+            if let Action::Become(ParticleKind::Water) = p.get_action(ceiling, &mut ctx) {
+                condensed = true;
+                break;
+            }
+        }
+        assert!(condensed);
+    }
+
+}
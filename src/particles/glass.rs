@@ -0,0 +1,37 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, GLASS};
+
+/// A clear solid which nothing, not even acid, eats into.
+#[derive(Copy, Clone)]
+pub struct GlassParticle;
+
+impl Particle for GlassParticle {
+    fn get_action(&mut self, _: Neighbours, _: &mut Context) -> Action {
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Glass
+    }
+    fn get_colour(&self) -> Colour {
+        GLASS
+    }
+    fn tick(&mut self){
+        // do nothing
+    }
+}
+
+impl Processable for GlassParticle {
+    fn get_was_processed(&self) -> bool {
+        true
+    }
+
+    fn set_processed(&mut self) {
+        // do nothing
+    }
+
+    fn set_not_processed(&mut self) {
+        // do nothing
+    }
+}
@@ -0,0 +1,51 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use super::powder;
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, GUNPOWDER};
+
+/// A dark powder which piles up like sand and burns very readily, making
+/// a good fuse.
+#[derive(Copy, Clone, Default)]
+pub struct GunpowderParticle {
+    processed: bool
+}
+
+impl GunpowderParticle {
+
+    pub fn new() -> GunpowderParticle {
+        GunpowderParticle {
+            processed: false
+        }
+    }
+
+}
+
+impl Particle for GunpowderParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        powder::fall(ParticleKind::Gunpowder, &neighbours, ctx.rng)
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Gunpowder
+    }
+    fn get_colour(&self) -> Colour {
+        GUNPOWDER
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+}
+
+impl Processable for GunpowderParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
@@ -0,0 +1,64 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, HEATER};
+
+/// Heat a powered heater gives off each tick, in degrees.
+pub const POWER: f32 = 20.0;
+
+/// Hottest a heater will get.
+pub const MAX_TEMPERATURE: f32 = 500.0;
+
+/// A solid which warms up while a pulse passes next to it.
+#[derive(Copy, Clone)]
+pub struct HeaterParticle {
+    processed: bool
+}
+
+impl HeaterParticle {
+
+    pub fn new() -> HeaterParticle {
+        HeaterParticle {
+            processed: false
+        }
+    }
+
+}
+
+impl Default for HeaterParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for HeaterParticle {
+    fn get_action(&mut self, _: Neighbours, ctx: &mut Context) -> Action {
+        if ctx.powered && ctx.temperature < MAX_TEMPERATURE {
+            ctx.temperature = (ctx.temperature + POWER).min(MAX_TEMPERATURE);
+        }
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Heater
+    }
+    fn get_colour(&self) -> Colour {
+        HEATER
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+}
+
+impl Processable for HeaterParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
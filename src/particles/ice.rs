@@ -0,0 +1,103 @@
+use super::particle::{Particle, Neighbours, Processable, Context, find_neighbour};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, ICE};
+
+/// Ice must be at least this cold to freeze the water next to it.
+pub const FREEZING_TEMPERATURE: f32 = -2.0;
+
+/// Chance per tick that ice which is cold enough freezes a neighbouring
+/// cell of water.
+pub const FREEZE_CHANCE: f32 = 0.02;
+
+/// Heat given up to the ice by water freezing onto it, which stops a single
+/// cold crystal freezing a whole lake.
+pub const LATENT_HEAT: f32 = 4.0;
+
+/// Frozen water, which stays put until it warms up enough to melt, and
+/// slowly freezes the water around it while it is cold.
+#[derive(Copy, Clone)]
+pub struct IceParticle {
+    processed: bool
+}
+
+impl IceParticle {
+
+    pub fn new() -> IceParticle {
+        IceParticle {
+            processed: false
+        }
+    }
+
+}
+
+impl Default for IceParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for IceParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        if ctx.temperature <= FREEZING_TEMPERATURE && ctx.rng.chance(FREEZE_CHANCE) {
+            if let Some((x, y)) = find_neighbour(&neighbours, ParticleKind::Water) {
+                ctx.temperature += LATENT_HEAT;
+                return Action::Freeze{x, y};
+            }
+        }
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Ice
+    }
+    fn get_colour(&self) -> Colour {
+        ICE
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+}
+
+impl Processable for IceParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    pub fn test_cold_ice_freezes_water() {
+        let mut rng = Rng::new(39);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = IceParticle::new();
+        let mut n = [[ParticleKind::Background; 3]; 3];
+        n[2][1] = ParticleKind::Water;
+
+        ctx.temperature = 0.0;
+        for _ in 0..1000 {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::StayPut));
+        }
+
+        ctx.temperature = -20.0;
+        let mut ticks = 0;
+        while matches!(p.get_action(n, &mut ctx), Action::StayPut) {
+            ticks += 1;
+        }
+        assert!(ticks > 0);
+        assert_eq!(ctx.temperature, -20.0 + LATENT_HEAT);
+    }
+
+}
@@ -0,0 +1,74 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, YELLOW, LAMP};
+
+/// How long a lamp stays lit after a pulse passes, in ticks; long enough
+/// that a steady train of pulses keeps it lit.
+pub const GLOW_TICKS: u32 = 4;
+
+/// A solid which lights up while pulses pass next to it.
+#[derive(Copy, Clone)]
+pub struct LampParticle {
+    processed: bool,
+    glow: u32
+}
+
+impl LampParticle {
+
+    pub fn new() -> LampParticle {
+        LampParticle {
+            processed: false,
+            glow: 0
+        }
+    }
+
+    pub fn is_lit(&self) -> bool {
+        self.glow > 0
+    }
+
+}
+
+impl Default for LampParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for LampParticle {
+    fn get_action(&mut self, _: Neighbours, ctx: &mut Context) -> Action {
+        if ctx.powered {
+            self.glow = GLOW_TICKS;
+        }
+        else {
+            self.glow = self.glow.saturating_sub(1);
+        }
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Lamp
+    }
+    fn get_colour(&self) -> Colour {
+        if self.is_lit() { YELLOW } else { LAMP }
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        self.glow as u64
+    }
+}
+
+impl Processable for LampParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
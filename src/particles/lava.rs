@@ -0,0 +1,143 @@
+use super::particle::{Particle, Neighbours, Processable, Context, find_neighbour};
+use super::liquid;
+use super::fire::ignite;
+use crate::actions::Action;
+use crate::heat::AMBIENT_TEMPERATURE;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, LAVA, ORANGE};
+
+/// Temperature lava comes out of the ground at.
+pub const ERUPTION_TEMPERATURE: f32 = 1200.0;
+
+/// Lava any cooler than this sets into stone.
+pub const SOLIDIFY_TEMPERATURE: f32 = 700.0;
+
+/// Stone any hotter than this melts back into lava. Higher than
+/// `SOLIDIFY_TEMPERATURE`, so that freshly set stone doesn't flicker back.
+pub const MELT_TEMPERATURE: f32 = 1000.0;
+
+/// Fraction of its difference from ambient temperature that lava loses each
+/// tick by glowing, on top of what it loses to its neighbours.
+pub const RADIANT_COOLING: f32 = 0.002;
+
+/// Chance per tick that lava moves at all, which makes it sluggish.
+pub const FLOW_CHANCE: f32 = 0.25;
+
+/// Temperature water quenches lava down to, as it boils away.
+pub const QUENCH_TEMPERATURE: f32 = 300.0;
+
+#[derive(Copy, Clone)]
+pub struct LavaParticle {
+    processed: bool,
+    last_dir_x: i32,
+    glowing: bool
+}
+
+impl LavaParticle {
+
+    pub fn new() -> LavaParticle {
+        LavaParticle {
+            processed: false,
+            last_dir_x: 1,
+            glowing: true
+        }
+    }
+
+}
+
+impl Default for LavaParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for LavaParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        // water boils off, and quenches us into stone
+        if let Some((x, y)) = find_neighbour(&neighbours, ParticleKind::Water) {
+            ctx.temperature = ctx.temperature.min(QUENCH_TEMPERATURE);
+            return Action::React{x, y, this: ParticleKind::Stone, that: ParticleKind::Steam};
+        }
+
+        ctx.temperature -= RADIANT_COOLING * (ctx.temperature - AMBIENT_TEMPERATURE);
+        self.glowing = ctx.temperature >= (SOLIDIFY_TEMPERATURE + ERUPTION_TEMPERATURE) / 2.0;
+
+        if let Some(action) = ignite(&neighbours, ctx.rng) {
+            return action;
+        }
+
+        if ctx.rng.chance(FLOW_CHANCE) {
+            liquid::flow(ParticleKind::Lava, &mut self.last_dir_x, &neighbours)
+        }
+        else {
+            Action::StayPut
+        }
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Lava
+    }
+    fn get_colour(&self) -> Colour {
+        if self.glowing { ORANGE } else { LAVA }
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        ((self.glowing as u64) << 32) | (self.last_dir_x as u32 as u64)
+    }
+}
+
+impl Processable for LavaParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    const AIR: Neighbours = [[ParticleKind::Background; 3]; 3];
+
+    #[test]
+    pub fn test_water_quenches_lava() {
+        let mut rng = Rng::new(36);
+        let mut ctx = Context::new(&mut rng);
+        ctx.temperature = ERUPTION_TEMPERATURE;
+        let mut n = AIR;
+        n[1][2] = ParticleKind::Water;
+        let action = LavaParticle::new().get_action(n, &mut ctx);
+        assert!(matches!(action, Action::React{x: 1, y: 0, this: ParticleKind::Stone, that: ParticleKind::Steam}));
+        // and the stone it leaves is cool enough to stay stone
+        assert_eq!(ParticleKind::Stone.transition(ctx.temperature), None);
+    }
+
+    #[test]
+    pub fn test_lava_cools_and_glows_less() {
+        let mut rng = Rng::new(36);
+        let mut ctx = Context::new(&mut rng);
+        ctx.temperature = ERUPTION_TEMPERATURE;
+        let mut lava = LavaParticle::new();
+        let mut n = AIR;
+        n[2] = [ParticleKind::Concrete; 3];
+        let mut ticks = 0;
+        while ParticleKind::Lava.transition(ctx.temperature).is_none() {
+            lava.get_action(n, &mut ctx);
+            ticks += 1;
+        }
+        assert!(ticks > 100);
+        assert!(!lava.glowing);
+        assert_eq!(ParticleKind::Lava.transition(ctx.temperature), Some(ParticleKind::Stone));
+    }
+
+}
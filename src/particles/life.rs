@@ -0,0 +1,37 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, LIFE};
+
+/// A cell which lives and dies by the game's life rule, rather than moving.
+#[derive(Copy, Clone)]
+pub struct LifeParticle;
+
+impl Particle for LifeParticle {
+    fn get_action(&mut self, _: Neighbours, _: &mut Context) -> Action {
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Life
+    }
+    fn get_colour(&self) -> Colour {
+        LIFE
+    }
+    fn tick(&mut self){
+        // do nothing
+    }
+}
+
+impl Processable for LifeParticle {
+    fn get_was_processed(&self) -> bool {
+        true
+    }
+
+    fn set_processed(&mut self) {
+        // do nothing
+    }
+
+    fn set_not_processed(&mut self) {
+        // do nothing
+    }
+}
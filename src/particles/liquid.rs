@@ -0,0 +1,40 @@
+use super::particle::{Neighbours, move_into};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+
+/// Movement rules shared by all liquids: fall, else flow down diagonally,
+/// else spread sideways. Liquids sink through lighter liquids, but only
+/// spread sideways into empty space so that layers don't mix.
+///
+/// `last_dir_x` remembers which way the liquid last flowed, so that it keeps
+/// going that way when it could go either.
+pub fn flow(kind: ParticleKind, last_dir_x: &mut i32, neighbours: &Neighbours) -> Action {
+    flow_with(last_dir_x, neighbours, |target, x, y| move_into(kind, target, x, y))
+}
+
+/// As `flow`, with `enter` deciding how (if at all) to move down into a
+/// cell given its contents and offset.
+pub fn flow_with<F>(last_dir_x: &mut i32, neighbours: &Neighbours, enter: F) -> Action
+where F: Fn(ParticleKind, i32, i32) -> Option<Action> {
+    let below = |dx: i32| enter(neighbours[2][(dx + 1) as usize], dx, 1);
+    let is_empty = |k: ParticleKind| k == ParticleKind::Background;
+
+    let b_e = below(0);
+    let ble = below(-1);
+    let bre = below(1);
+    let jle = is_empty(neighbours[1][0]);
+    let jre = is_empty(neighbours[1][2]);
+
+    // if below is free...
+    if let Some(action) = b_e { action }
+    // if below right/left are free...
+    else if ble.is_some() && bre.is_some() { below(*last_dir_x).unwrap() }
+    else if let Some(action) = bre { *last_dir_x = 1; action }
+    else if let Some(action) = ble { *last_dir_x = -1; action }
+    // if direct right/left are empty...
+    else if jle && jre { Action::MoveInto{x: *last_dir_x, y: 0} }
+    else if jre { *last_dir_x = 1; Action::MoveInto{x: 1, y: 0} }
+    else if jle { *last_dir_x = -1; Action::MoveInto{x:-1, y: 0} }
+    // otherwise, do nothing
+    else {Action::StayPut}
+}
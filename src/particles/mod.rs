@@ -1,7 +1,35 @@
+pub mod acid;
+pub mod ash;
 pub mod background;
+pub mod battery;
+pub mod brine;
 pub mod concrete;
+pub mod drain;
+pub mod fire;
+pub mod gas;
+pub mod glass;
+pub mod gunpowder;
+pub mod heater;
+pub mod ice;
+pub mod lamp;
+pub mod lava;
+pub mod life;
+pub mod liquid;
+pub mod oil;
 pub mod particle;
+pub mod plant;
+pub mod powder;
 pub mod salt;
+pub mod snow;
+pub mod soil;
+pub mod source;
+pub mod sand;
+pub mod seed;
+pub mod stone;
+pub mod switch;
+pub mod tnt;
 pub mod water;
+pub mod wire;
+pub mod wood;
 pub mod processable;
 pub mod cornucopia;
\ No newline at end of file
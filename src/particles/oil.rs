@@ -0,0 +1,67 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use super::liquid;
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, OIL};
+
+/// Chance per tick that oil which could spread sideways does so, which
+/// makes it spread more slowly than water.
+pub const SPREAD_CHANCE: f32 = 0.2;
+
+#[derive(Copy, Clone)]
+pub struct OilParticle {
+    processed: bool,
+    last_dir_x: i32
+}
+
+impl OilParticle {
+
+    pub fn new() -> OilParticle {
+        OilParticle {
+            processed: false,
+            last_dir_x: 1
+        }
+    }
+
+}
+
+impl Default for OilParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for OilParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        match liquid::flow(ParticleKind::Oil, &mut self.last_dir_x, &neighbours) {
+            Action::MoveInto{y: 0, ..} if !ctx.rng.chance(SPREAD_CHANCE) => Action::StayPut,
+            action => action
+        }
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Oil
+    }
+    fn get_colour(&self) -> Colour {
+        OIL
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        self.last_dir_x as u64
+    }
+}
+
+impl Processable for OilParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
@@ -1,13 +1,116 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
 use crate::actions::Action;
 use crate::kinds::ParticleKind;
 use crate::colour::Colour;
+use crate::heat::AMBIENT_TEMPERATURE;
+use crate::rng::Rng;
 pub use super::processable::Processable;
 
 pub type Neighbours = [[ParticleKind; 3]; 3];
 
-pub trait Particle : Processable {
-    fn get_action(&mut self, neighbours: Neighbours) -> Action;
+/// Offsets (x, y) of the eight neighbours of a cell.
+pub const NEIGHBOUR_OFFSETS: [(i32, i32); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0), (1, 0),
+    (-1, 1), (0, 1), (1, 1)
+];
+
+/// What a particle knows about its own cell, beyond its neighbours' kinds.
+pub struct Context<'a> {
+    /// The game's random number generator; particles should take all of
+    /// their randomness from here so that games can be replayed.
+    pub rng: &'a mut Rng,
+    /// Temperature of the cell, in degrees C. A particle may change this,
+    /// e.g. to keep itself hot, and the change is kept.
+    pub temperature: f32,
+    /// Whether an electrical pulse is passing through a neighbouring cell.
+    pub powered: bool
+}
+
+impl<'a> Context<'a> {
+    /// An unpowered cell at ambient temperature.
+    pub fn new(rng: &'a mut Rng) -> Context<'a> {
+        Context {
+            rng,
+            temperature: AMBIENT_TEMPERATURE,
+            powered: false
+        }
+    }
+}
+
+pub trait Particle : Processable + Snapshot {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action;
     fn get_type(&self) -> ParticleKind;
     fn get_colour(&self) -> Colour;
     fn tick(&mut self);
-}
\ No newline at end of file
+
+    /// Any internal state which affects how the particle will behave, packed
+    /// into a number for hashing the world. Transient bookkeeping, like
+    /// whether the particle was processed this tick, is not included.
+    fn get_state(&self) -> u64 {
+        0
+    }
+
+    /// A copy of this particle, to fill the neighbouring cell when it
+    /// divides. Only particles which divide need to provide one.
+    fn divide(&self) -> Option<Rc<RefCell<dyn Particle>>> {
+        None
+    }
+
+    /// Whether the particle can carry electrical pulses at the moment.
+    fn conducts(&self) -> bool {
+        self.get_type() == ParticleKind::Wire
+    }
+
+    /// Flip the particle between on and off, for particles which have an on
+    /// and an off, like switches.
+    fn toggle(&mut self) {
+        // most particles can't be switched
+    }
+}
+
+/// Copying a particle, state and all, so the game can put it back as it was
+/// if what it decided to do is called off.
+pub trait Snapshot {
+    fn snapshot(&self) -> Rc<RefCell<dyn Particle>>;
+}
+
+impl<T: Particle + Clone + 'static> Snapshot for T {
+    fn snapshot(&self) -> Rc<RefCell<dyn Particle>> {
+        Rc::new(RefCell::new(self.clone()))
+    }
+}
+
+/// The neighbours as seen upside down.
+pub fn flip_vertically(neighbours: &Neighbours) -> Neighbours {
+    [neighbours[2], neighbours[1], neighbours[0]]
+}
+
+/// Action for a particle of `kind` to move into the neighbouring cell at
+/// (`x`, `y`), currently holding `target`: it moves into empty space and
+/// swaps places with lighter liquids, but otherwise can't move there.
+pub fn move_into(kind: ParticleKind, target: ParticleKind, x: i32, y: i32) -> Option<Action> {
+    if target == ParticleKind::Background {
+        Some(Action::MoveInto{x, y})
+    }
+    else if kind.can_displace(target) {
+        Some(Action::SwapWith{x, y})
+    }
+    else {
+        None
+    }
+}
+
+/// Offset of the first neighbour of the given kind, if any.
+pub fn find_neighbour(neighbours: &Neighbours, kind: ParticleKind) -> Option<(i32, i32)> {
+    for (i, row) in neighbours.iter().enumerate() {
+        for (j, k) in row.iter().enumerate() {
+            if (i, j) != (1, 1) && *k == kind {
+                return Some((j as i32 - 1, i as i32 - 1));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,241 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use super::particle::{Particle, Neighbours, Processable, Context, find_neighbour};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, GREEN, STEM, WILTED};
+
+/// Water taken up by drinking a single cell of water.
+pub const DRINK: u32 = 100;
+
+/// Most water a cell of plant can hold.
+pub const MAX_MOISTURE: u32 = 400;
+
+/// A cell of plant needs at least this much water to grow.
+pub const GROW_MOISTURE: u32 = 20;
+
+/// Chance per tick that a cell of plant with enough water grows.
+pub const GROW_CHANCE: f32 = 0.1;
+
+/// Chance per tick that a cell of plant loses a unit of water to the air.
+pub const TRANSPIRATION_CHANCE: f32 = 0.1;
+
+/// Chance per tick that a wilted cell of plant rots into soil.
+pub const ROT_CHANCE: f32 = 0.002;
+
+/// Chance that a growing stem sprouts a leaf rather than growing taller.
+pub const LEAF_CHANCE: f32 = 0.3;
+
+/// Directions a stem grows taller in; mostly straight up.
+const STEM_GROWTH: [(i32, i32); 4] = [(0, -1), (0, -1), (-1, -1), (1, -1)];
+
+/// Directions a stem sprouts leaves in, out to either side.
+const LEAF_GROWTH: [(i32, i32); 2] = [(-1, 0), (1, 0)];
+
+/// Which part of a plant a cell of plant is.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Part {
+    /// Grows upwards, and sprouts leaves to the side.
+    Stem,
+    /// Drinks, but grows no further.
+    Leaf
+}
+
+/// A living plant, which drinks the water it touches. Its stems grow by
+/// dividing, sharing their water with the new cell, which is either more
+/// stem or a leaf. It wilts once it runs dry, and rots into soil unless it
+/// finds water again.
+#[derive(Copy, Clone)]
+pub struct PlantParticle {
+    processed: bool,
+    moisture: u32,
+    part: Part,
+    /// Which part the cell this one last divided into grows as.
+    sprouting: Part
+}
+
+impl PlantParticle {
+
+    /// A plant just sprouted from a seed, with the drink of water it took to
+    /// sprout.
+    pub fn new() -> PlantParticle {
+        PlantParticle {
+            processed: false,
+            moisture: DRINK,
+            part: Part::Stem,
+            sprouting: Part::Stem
+        }
+    }
+
+    pub fn get_moisture(&self) -> u32 {
+        self.moisture
+    }
+
+    pub fn get_part(&self) -> Part {
+        self.part
+    }
+
+    pub fn is_wilted(&self) -> bool {
+        self.moisture == 0
+    }
+
+}
+
+impl Default for PlantParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for PlantParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        if self.moisture > 0 && ctx.rng.chance(TRANSPIRATION_CHANCE) {
+            self.moisture -= 1;
+        }
+
+        if self.moisture + DRINK <= MAX_MOISTURE {
+            if let Some((x, y)) = find_neighbour(&neighbours, ParticleKind::Water) {
+                self.moisture += DRINK;
+                return Action::React{x, y, this: ParticleKind::Plant, that: ParticleKind::Background};
+            }
+        }
+
+        if self.is_wilted() && ctx.rng.chance(ROT_CHANCE) {
+            return Action::Become(ParticleKind::Soil);
+        }
+
+        if self.part == Part::Stem && self.moisture >= GROW_MOISTURE && ctx.rng.chance(GROW_CHANCE) {
+            let (part, growth) = if ctx.rng.chance(LEAF_CHANCE) {
+                (Part::Leaf, &LEAF_GROWTH[..])
+            } else {
+                (Part::Stem, &STEM_GROWTH[..])
+            };
+            let (x, y) = growth[ctx.rng.below(growth.len() as u32) as usize];
+            if neighbours[(y + 1) as usize][(x + 1) as usize] == ParticleKind::Background {
+                self.moisture /= 2;
+                self.sprouting = part;
+                return Action::Divide{x, y};
+            }
+        }
+
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Plant
+    }
+    fn get_colour(&self) -> Colour {
+        match self.part {
+            _ if self.is_wilted() => WILTED,
+            Part::Stem => STEM,
+            Part::Leaf => GREEN
+        }
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        ((self.sprouting as u64) << 40) | ((self.part as u64) << 32) | self.moisture as u64
+    }
+    fn divide(&self) -> Option<Rc<RefCell<dyn Particle>>> {
+        Some(Rc::new(RefCell::new(PlantParticle{part: self.sprouting, ..*self})))
+    }
+}
+
+impl Processable for PlantParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    const B: ParticleKind = ParticleKind::Background;
+
+    #[test]
+    pub fn test_plant_shares_water_when_it_grows() {
+        let mut rng = Rng::new(41);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = PlantParticle::new();
+        let action = loop {
+            match p.get_action([[B; 3]; 3], &mut ctx) {
+                Action::StayPut => continue,
+                other => break other
+            }
+        };
+        assert!(matches!(action, Action::Divide{y: -1..=0, ..}));
+        let child = p.divide().unwrap();
+        assert_eq!(child.borrow().get_state() as u32, p.get_moisture());
+        assert!(p.get_moisture() < DRINK);
+    }
+
+    #[test]
+    pub fn test_stems_grow_up_and_leaves_out() {
+        let mut rng = Rng::new(41);
+        let mut ctx = Context::new(&mut rng);
+        let (mut stems, mut leaves) = (0, 0);
+        for _ in 0..200 {
+            let mut p = PlantParticle::new();
+            let action = loop {
+                match p.get_action([[B; 3]; 3], &mut ctx) {
+                    Action::StayPut => continue,
+                    other => break other
+                }
+            };
+            let Action::Divide{y, ..} = action else { panic!("plant didn't grow") };
+            let child = p.divide().unwrap();
+            if (child.borrow().get_state() >> 32) & 0xff == Part::Stem as u64 {
+                assert_eq!(y, -1);
+                stems += 1;
+            }
+            else {
+                assert_eq!(y, 0);
+                leaves += 1;
+            }
+        }
+        assert!(stems > leaves && leaves > 0);
+
+        // leaves drink, but never grow
+        let mut leaf = PlantParticle::new();
+        leaf.part = Part::Leaf;
+        leaf.moisture = MAX_MOISTURE;
+        for _ in 0..1000 {
+            assert!(matches!(leaf.get_action([[B; 3]; 3], &mut ctx), Action::StayPut));
+        }
+        assert_eq!(leaf.get_colour().as_i(), GREEN.as_i());
+    }
+
+    #[test]
+    pub fn test_plant_drinks_and_wilts() {
+        let mut rng = Rng::new(41);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = PlantParticle::new();
+        let mut n = [[ParticleKind::Concrete; 3]; 3];
+        n[2][1] = ParticleKind::Water;
+        assert!(matches!(p.get_action(n, &mut ctx), Action::React{x: 0, y: 1, ..}));
+        assert!(p.get_moisture() > DRINK);
+
+        n[2][1] = ParticleKind::Concrete;
+        while !p.is_wilted() {
+            p.get_action(n, &mut ctx);
+        }
+        assert_eq!(p.get_colour().as_i(), WILTED.as_i());
+
+        // and rots away if it stays dry
+        let rotted = (0..100_000).any(|_| matches!(p.get_action(n, &mut ctx), Action::Become(ParticleKind::Soil)));
+        assert!(rotted);
+    }
+
+}
@@ -0,0 +1,55 @@
+use super::particle::{Neighbours, move_into};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::rng::Rng;
+
+/// Movement rules shared by all powders: fall, else slide down diagonally,
+/// to either side at random if both are free. Powders sink through lighter
+/// liquids and gases, and pile up on anything else.
+pub fn fall(kind: ParticleKind, neighbours: &Neighbours, rng: &mut Rng) -> Action {
+    below(kind, neighbours, 0)
+        .or_else(|| slide(kind, neighbours, rng))
+        .unwrap_or(Action::StayPut)
+}
+
+/// Action for a grain of `kind` to move into the cell below it, shifted `dx`
+/// across, if it can.
+pub fn below(kind: ParticleKind, neighbours: &Neighbours, dx: i32) -> Option<Action> {
+    move_into(kind, neighbours[2][(dx + 1) as usize], dx, 1)
+}
+
+/// Action for a grain of `kind` to slide down diagonally, if it can.
+pub fn slide(kind: ParticleKind, neighbours: &Neighbours, rng: &mut Rng) -> Option<Action> {
+    match (below(kind, neighbours, -1), below(kind, neighbours, 1)) {
+        (Some(l), Some(r)) => Some(if rng.chance(0.5) { l } else { r }),
+        (l, r) => l.or(r)
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    const B: ParticleKind = ParticleKind::Background;
+    const C: ParticleKind = ParticleKind::Concrete;
+    const W: ParticleKind = ParticleKind::Water;
+
+    #[test]
+    pub fn test_fall() {
+        let mut rng = Rng::new(40);
+        let sand = ParticleKind::Sand;
+        assert!(matches!(fall(sand, &[[B; 3]; 3], &mut rng), Action::MoveInto{x: 0, y: 1}));
+        assert!(matches!(fall(sand, &[[B, B, B], [B, B, B], [C, W, C]], &mut rng), Action::SwapWith{x: 0, y: 1}));
+        assert!(matches!(fall(sand, &[[B, B, B], [B, B, B], [C, C, B]], &mut rng), Action::MoveInto{x: 1, y: 1}));
+        assert!(matches!(fall(sand, &[[B, B, B], [B, B, B], [C, C, C]], &mut rng), Action::StayPut));
+
+        // either way down, if both are free
+        let sides: Vec<i32> = (0..20).map(|_| match fall(sand, &[[B, B, B], [B, B, B], [B, C, B]], &mut rng) {
+            Action::MoveInto{x, y: 1} => x,
+            _ => 0
+        }).collect();
+        assert!(sides.contains(&-1) && sides.contains(&1));
+    }
+}
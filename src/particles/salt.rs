@@ -1,4 +1,4 @@
-use super::particle::{Particle, Neighbours, Processable};
+use super::particle::{Particle, Neighbours, Processable, Context, move_into};
 use crate::actions::Action;
 use crate::kinds::ParticleKind;
 use crate::colour::{Colour, WHITE};
@@ -15,27 +15,13 @@ impl SaltParticle {
             processed: false
         }
     }
-
-    fn is_solvent(k: ParticleKind) -> bool {
-        k == ParticleKind::Water
-    }
-
 }
 
 impl Particle for SaltParticle {
-    fn get_action(&mut self, neighbours: Neighbours) -> Action {
-        if Self::is_solvent(neighbours[0][0]) || Self::is_solvent(neighbours[0][1])
-            || Self::is_solvent(neighbours[0][2]) || Self::is_solvent(neighbours[1][0])
-            || Self::is_solvent(neighbours[1][2]) || Self::is_solvent(neighbours[2][0])
-            || Self::is_solvent(neighbours[2][1]) || Self::is_solvent(neighbours[2][2]) {
-                Action::Pop
-        }
-        else if neighbours[1 + 1][0 + 1] == ParticleKind::Background {
-            Action::MoveInto{x:0, y:1}
-        }
-        else {
-            Action::StayPut
-        }
+    fn get_action(&mut self, neighbours: Neighbours, _: &mut Context) -> Action {
+        // salt is dissolved by the water it touches, rather than doing the
+        // dissolving, so all it does is fall (or sink)
+        move_into(ParticleKind::Salt, neighbours[2][1], 0, 1).unwrap_or(Action::StayPut)
     }
     fn get_type(&self) -> ParticleKind {
         ParticleKind::Salt
@@ -0,0 +1,116 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use super::powder;
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, SAND};
+
+/// Chance that a grain sitting on something will slide off diagonally, if it
+/// can. A grain which always slides piles up at 45 degrees, and lower chances
+/// give steeper piles.
+pub const DEFAULT_SLIDE_CHANCE: f32 = 0.8;
+
+#[derive(Copy, Clone)]
+pub struct SandParticle {
+    processed: bool,
+    slide_chance: f32
+}
+
+impl SandParticle {
+
+    pub fn new() -> SandParticle {
+        SandParticle::with_slide_chance(DEFAULT_SLIDE_CHANCE)
+    }
+
+    pub fn with_slide_chance(slide_chance: f32) -> SandParticle {
+        SandParticle {
+            processed: false,
+            slide_chance
+        }
+    }
+}
+
+impl Default for SandParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for SandParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        if let Some(action) = powder::below(ParticleKind::Sand, &neighbours, 0) {
+            return action;
+        }
+
+        if !ctx.rng.chance(self.slide_chance) {
+            return Action::StayPut;
+        }
+
+        powder::slide(ParticleKind::Sand, &neighbours, ctx.rng).unwrap_or(Action::StayPut)
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Sand
+    }
+    fn get_colour(&self) -> Colour {
+        SAND
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        self.slide_chance.to_bits() as u64
+    }
+}
+
+impl Processable for SandParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    const B: ParticleKind = ParticleKind::Background;
+    const C: ParticleKind = ParticleKind::Concrete;
+    const W: ParticleKind = ParticleKind::Water;
+
+    #[test]
+    pub fn test_falls_and_sinks() {
+        let mut rng = Rng::new(0);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = SandParticle::new();
+        assert!(matches!(p.get_action([[B; 3]; 3], &mut ctx), Action::MoveInto{x: 0, y: 1}));
+        let n = [[B, B, B], [B, B, B], [C, W, C]];
+        assert!(matches!(p.get_action(n, &mut ctx), Action::SwapWith{x: 0, y: 1}));
+    }
+
+    #[test]
+    pub fn test_slide_chance() {
+        let mut rng = Rng::new(0);
+        let mut ctx = Context::new(&mut rng);
+        let n = [[B, B, B], [B, B, B], [C, C, B]];
+
+        let mut p = SandParticle::with_slide_chance(0.0);
+        for _ in 0..100 {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::StayPut));
+        }
+
+        let mut p = SandParticle::with_slide_chance(1.0);
+        for _ in 0..100 {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::MoveInto{x: 1, y: 1}));
+        }
+    }
+
+}
@@ -0,0 +1,57 @@
+use super::particle::{Particle, Neighbours, Processable, Context, find_neighbour};
+use super::powder;
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, SEED};
+
+/// A seed, which falls and piles like sand, and sprouts into a plant when it
+/// lands on soil with water nearby.
+#[derive(Copy, Clone, Default)]
+pub struct SeedParticle {
+    processed: bool
+}
+
+impl SeedParticle {
+
+    pub fn new() -> SeedParticle {
+        SeedParticle {
+            processed: false
+        }
+    }
+
+}
+
+impl Particle for SeedParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        if neighbours[2][1] == ParticleKind::Soil {
+            if let Some((x, y)) = find_neighbour(&neighbours, ParticleKind::Water) {
+                return Action::React{x, y, this: ParticleKind::Plant, that: ParticleKind::Background};
+            }
+        }
+
+        powder::fall(ParticleKind::Seed, &neighbours, ctx.rng)
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Seed
+    }
+    fn get_colour(&self) -> Colour {
+        SEED
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+}
+
+impl Processable for SeedParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
@@ -0,0 +1,134 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use super::powder;
+use crate::actions::Action;
+use crate::kinds::{ParticleKind, Phase};
+use crate::colour::{Colour, WHITE};
+
+/// Chance per tick that a falling flake is blown sideways as it falls.
+pub const DRIFT_CHANCE: f32 = 0.4;
+
+/// How long snow has to be weighed down by something on top of it before it
+/// packs into ice, in ticks.
+pub const PACK_TICKS: u32 = 300;
+
+/// A light powder which drifts as it falls, and packs into ice under load.
+#[derive(Copy, Clone)]
+pub struct SnowParticle {
+    processed: bool,
+    loaded_for: u32
+}
+
+impl SnowParticle {
+
+    pub fn new() -> SnowParticle {
+        SnowParticle {
+            processed: false,
+            loaded_for: 0
+        }
+    }
+
+}
+
+impl Default for SnowParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for SnowParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        if let Some(action) = powder::below(ParticleKind::Snow, &neighbours, 0) {
+            self.loaded_for = 0;
+            if ctx.rng.chance(DRIFT_CHANCE) {
+                return powder::slide(ParticleKind::Snow, &neighbours, ctx.rng).unwrap_or(action);
+            }
+            return action;
+        }
+
+        let above = neighbours[0][1].phase();
+        if above == Phase::Solid || above == Phase::Powder {
+            self.loaded_for += 1;
+            if self.loaded_for >= PACK_TICKS {
+                return Action::Become(ParticleKind::Ice);
+            }
+        }
+        else {
+            self.loaded_for = 0;
+        }
+
+        powder::slide(ParticleKind::Snow, &neighbours, ctx.rng).unwrap_or(Action::StayPut)
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Snow
+    }
+    fn get_colour(&self) -> Colour {
+        WHITE
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        self.loaded_for as u64
+    }
+}
+
+impl Processable for SnowParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    const B: ParticleKind = ParticleKind::Background;
+
+    #[test]
+    pub fn test_snow_drifts() {
+        let mut rng = Rng::new(39);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = SnowParticle::new();
+        let (mut left, mut right) = (false, false);
+        for _ in 0..100 {
+            match p.get_action([[B; 3]; 3], &mut ctx) {
+                Action::MoveInto{x: -1, y: 1} => left = true,
+                Action::MoveInto{x: 1, y: 1} => right = true,
+                Action::MoveInto{x: 0, y: 1} => (),
+                _ => panic!("snow should always fall through air")
+            }
+        }
+        assert!(left && right);
+    }
+
+    #[test]
+    pub fn test_snow_packs_under_load() {
+        let mut rng = Rng::new(39);
+        let mut ctx = Context::new(&mut rng);
+        let mut p = SnowParticle::new();
+        let mut n = [[ParticleKind::Snow; 3]; 3];
+        n[1] = [ParticleKind::Concrete; 3];
+        for _ in 1..PACK_TICKS {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::StayPut));
+        }
+        assert!(matches!(p.get_action(n, &mut ctx), Action::Become(ParticleKind::Ice)));
+
+        // but not with nothing on top
+        let mut p = SnowParticle::new();
+        n[0] = [B; 3];
+        for _ in 0..PACK_TICKS * 2 {
+            assert!(matches!(p.get_action(n, &mut ctx), Action::StayPut));
+        }
+    }
+
+}
@@ -0,0 +1,50 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use super::powder;
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, SOIL};
+
+/// Earth, which piles up like sand, and which seeds can take root in.
+#[derive(Copy, Clone, Default)]
+pub struct SoilParticle {
+    processed: bool
+}
+
+impl SoilParticle {
+
+    pub fn new() -> SoilParticle {
+        SoilParticle {
+            processed: false
+        }
+    }
+
+}
+
+impl Particle for SoilParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        powder::fall(ParticleKind::Soil, &neighbours, ctx.rng)
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Soil
+    }
+    fn get_colour(&self) -> Colour {
+        SOIL
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+}
+
+impl Processable for SoilParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
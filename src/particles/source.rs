@@ -0,0 +1,118 @@
+use super::particle::{Particle, Neighbours, Processable, Context, NEIGHBOUR_OFFSETS};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, SOURCE};
+
+/// What a newly painted source emits, unless told otherwise.
+pub const DEFAULT_KIND: ParticleKind = ParticleKind::Water;
+
+/// Chance per tick that a newly painted source emits a particle, unless told
+/// otherwise.
+pub const DEFAULT_RATE: f32 = 0.5;
+
+/// A tap, which emits particles of a chosen kind into the empty cells
+/// around it at a chosen rate.
+#[derive(Copy, Clone)]
+pub struct SourceParticle {
+    processed: bool,
+    kind: ParticleKind,
+    rate: f32
+}
+
+impl SourceParticle {
+
+    pub fn new() -> SourceParticle {
+        SourceParticle::with_settings(DEFAULT_KIND, DEFAULT_RATE)
+    }
+
+    /// A source emitting `kind`, with chance `rate` per tick.
+    pub fn with_settings(kind: ParticleKind, rate: f32) -> SourceParticle {
+        SourceParticle {
+            processed: false,
+            kind,
+            rate: rate.clamp(0.0, 1.0)
+        }
+    }
+
+    pub fn get_kind(&self) -> ParticleKind {
+        self.kind
+    }
+
+    pub fn get_rate(&self) -> f32 {
+        self.rate
+    }
+
+}
+
+impl Default for SourceParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for SourceParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        if !ctx.rng.chance(self.rate) {
+            return Action::StayPut;
+        }
+
+        // start from a random neighbour, so the flow doesn't favour any side
+        let start = ctx.rng.below(8) as usize;
+        for i in 0..8 {
+            let (x, y) = NEIGHBOUR_OFFSETS[(start + i) % 8];
+            if neighbours[(y + 1) as usize][(x + 1) as usize] == ParticleKind::Background {
+                return Action::GrowInto{x, y, kind: self.kind};
+            }
+        }
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Source
+    }
+    fn get_colour(&self) -> Colour {
+        SOURCE
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+    fn get_state(&self) -> u64 {
+        (self.kind as u64) << 32 | self.rate.to_bits() as u64
+    }
+}
+
+impl Processable for SourceParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    pub fn test_source_rate() {
+        let mut rng = Rng::new(43);
+        let mut ctx = Context::new(&mut rng);
+        let n = [[ParticleKind::Background; 3]; 3];
+        let mut count = |rate| {
+            let mut p = SourceParticle::with_settings(ParticleKind::Sand, rate);
+            (0..1000).filter(|_| matches!(p.get_action(n, &mut ctx), Action::GrowInto{kind: ParticleKind::Sand, ..})).count()
+        };
+        assert_eq!(count(0.0), 0);
+        assert_eq!(count(1.0), 1000);
+        assert!((200..300).contains(&count(0.25)));
+        assert_eq!(SourceParticle::with_settings(ParticleKind::Sand, 3.0).get_rate(), 1.0);
+    }
+
+}
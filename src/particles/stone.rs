@@ -0,0 +1,37 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, STONE};
+
+/// Set lava, which stays put unless it is heated enough to melt again.
+#[derive(Copy, Clone)]
+pub struct StoneParticle;
+
+impl Particle for StoneParticle {
+    fn get_action(&mut self, _: Neighbours, _: &mut Context) -> Action {
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Stone
+    }
+    fn get_colour(&self) -> Colour {
+        STONE
+    }
+    fn tick(&mut self){
+        // do nothing
+    }
+}
+
+impl Processable for StoneParticle {
+    fn get_was_processed(&self) -> bool {
+        true
+    }
+
+    fn set_processed(&mut self) {
+        // do nothing
+    }
+
+    fn set_not_processed(&mut self) {
+        // do nothing
+    }
+}
@@ -0,0 +1,63 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, SWITCH_ON, SWITCH_OFF};
+
+/// A piece of wire which can be switched off, so that it stops carrying
+/// pulses. Switches start off.
+#[derive(Copy, Clone, Default)]
+pub struct SwitchParticle {
+    on: bool
+}
+
+impl SwitchParticle {
+
+    pub fn new() -> SwitchParticle {
+        SwitchParticle {
+            on: false
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+}
+
+impl Particle for SwitchParticle {
+    fn get_action(&mut self, _: Neighbours, _: &mut Context) -> Action {
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Switch
+    }
+    fn get_colour(&self) -> Colour {
+        if self.on { SWITCH_ON } else { SWITCH_OFF }
+    }
+    fn tick(&mut self){
+        // do nothing
+    }
+    fn get_state(&self) -> u64 {
+        self.on as u64
+    }
+    fn conducts(&self) -> bool {
+        self.on
+    }
+    fn toggle(&mut self) {
+        self.on = !self.on;
+    }
+}
+
+impl Processable for SwitchParticle {
+    fn get_was_processed(&self) -> bool {
+        true
+    }
+
+    fn set_processed(&mut self) {
+        // do nothing
+    }
+
+    fn set_not_processed(&mut self) {
+        // do nothing
+    }
+}
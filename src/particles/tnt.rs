@@ -0,0 +1,69 @@
+use super::particle::{Particle, Neighbours, Processable, Context, find_neighbour};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, TNT};
+
+/// Radius of the blast a single block of TNT makes, in cells.
+pub const BLAST_RADIUS: u32 = 6;
+
+/// TNT any hotter than this goes off.
+pub const DETONATION_TEMPERATURE: f32 = 200.0;
+
+/// A solid explosive, which blows up when it touches a flame or gets hot,
+/// for instance from another blast nearby.
+#[derive(Copy, Clone)]
+pub struct TntParticle {
+    processed: bool
+}
+
+impl TntParticle {
+
+    pub fn new() -> TntParticle {
+        TntParticle {
+            processed: false
+        }
+    }
+
+}
+
+impl Default for TntParticle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Particle for TntParticle {
+    fn get_action(&mut self, neighbours: Neighbours, ctx: &mut Context) -> Action {
+        let lit = find_neighbour(&neighbours, ParticleKind::Fire).is_some()
+            || find_neighbour(&neighbours, ParticleKind::Lava).is_some();
+        if lit || ctx.temperature >= DETONATION_TEMPERATURE {
+            Action::Explode{radius: BLAST_RADIUS}
+        }
+        else {
+            Action::StayPut
+        }
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Tnt
+    }
+    fn get_colour(&self) -> Colour {
+        TNT
+    }
+    fn tick(&mut self){
+        self.set_not_processed();
+    }
+}
+
+impl Processable for TntParticle {
+    fn get_was_processed(&self) -> bool {
+        self.processed
+    }
+
+    fn set_processed(&mut self) {
+        self.processed = true;
+    }
+
+    fn set_not_processed(&mut self) {
+        self.processed = false
+    }
+}
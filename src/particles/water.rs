@@ -1,4 +1,5 @@
-use super::particle::{Particle, Neighbours, Processable};
+use super::particle::{Particle, Neighbours, Processable, Context, find_neighbour};
+use super::liquid;
 use crate::actions::Action;
 use crate::kinds::ParticleKind;
 use crate::colour::{Colour, BLUE};
@@ -17,35 +18,23 @@ impl WaterParticle {
             last_dir_x: 1
         }
     }
+}
 
-    fn is_empty(k: ParticleKind) -> bool {
-        k == ParticleKind::Background
+impl Default for WaterParticle {
+    fn default() -> Self {
+        Self::new()
     }
-
 }
 
 impl Particle for WaterParticle {
 
-    fn get_action(&mut self, neighbours: Neighbours) -> Action {
-
-        let b_e = Self::is_empty(neighbours[2][1]);
-        let ble = Self::is_empty(neighbours[2][0]);
-        let bre = Self::is_empty(neighbours[2][2]);
-        let jle = Self::is_empty(neighbours[1][0]);
-        let jre = Self::is_empty(neighbours[1][2]);
+    fn get_action(&mut self, neighbours: Neighbours, _: &mut Context) -> Action {
+        // dissolve any salt we touch
+        if let Some((x, y)) = find_neighbour(&neighbours, ParticleKind::Salt) {
+            return Action::React{x, y, this: ParticleKind::Brine, that: ParticleKind::Background};
+        }
 
-        // if below is empty...
-        if b_e { Action::MoveInto{x: 0, y: 1} }
-        // if below right/left are empty...
-        else if ble && bre { Action::MoveInto{x: self.last_dir_x, y: 1} }
-        else if bre { self.last_dir_x = 1; Action::MoveInto{x: 1, y: 1} }
-        else if ble { self.last_dir_x = -1; Action::MoveInto{x:-1, y: 1} }
-        // if direct right/left are empty...
-        else if jle && jre { Action::MoveInto{x: self.last_dir_x, y: 0} }
-        else if neighbours[1][2] == ParticleKind::Background { self.last_dir_x = 1; Action::MoveInto{x: 1, y: 0} }
-        else if neighbours[1][0] == ParticleKind::Background { self.last_dir_x = -1; Action::MoveInto{x:-1, y: 0} }
-        // otherwise, do nothing
-        else {Action::StayPut}
+        liquid::flow(ParticleKind::Water, &mut self.last_dir_x, &neighbours)
     }
     fn get_type(&self) -> ParticleKind {
         ParticleKind::Water
@@ -56,6 +45,9 @@ impl Particle for WaterParticle {
     fn tick(&mut self){
         self.set_not_processed();
     }
+    fn get_state(&self) -> u64 {
+        self.last_dir_x as u64
+    }
 }
 
 impl Processable for WaterParticle {
@@ -0,0 +1,37 @@
+use super::particle::{Particle, Neighbours, Processable, Context};
+use crate::actions::Action;
+use crate::kinds::ParticleKind;
+use crate::colour::{Colour, WIRE};
+
+/// Metal wire, which carries electrical pulses.
+#[derive(Copy, Clone)]
+pub struct WireParticle;
+
+impl Particle for WireParticle {
+    fn get_action(&mut self, _: Neighbours, _: &mut Context) -> Action {
+        Action::StayPut
+    }
+    fn get_type(&self) -> ParticleKind {
+        ParticleKind::Wire
+    }
+    fn get_colour(&self) -> Colour {
+        WIRE
+    }
+    fn tick(&mut self){
+        // do nothing
+    }
+}
+
+impl Processable for WireParticle {
+    fn get_was_processed(&self) -> bool {
+        true
+    }
+
+    fn set_processed(&mut self) {
+        // do nothing
+    }
+
+    fn set_not_processed(&mut self) {
+        // do nothing
+    }
+}
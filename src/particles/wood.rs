@@ -1,4 +1,4 @@
-use super::particle::{Particle, Neighbours};
+use super::particle::{Particle, Neighbours, Processable, Context};
 use crate::actions::Action;
 use crate::kinds::ParticleKind;
 use crate::colour::{Colour, BROWN};
@@ -7,17 +7,9 @@ use crate::colour::{Colour, BROWN};
 pub struct WoodParticle;
 
 impl Particle for WoodParticle {
-    fn get_action(&self, neighbours: Neighbours) -> Action {
-        // if below is empty...
-        if neighbours[2][1] == ParticleKind::Background { Action::MoveInto{x: 0, y: 1} }
-        // if below right/left are empty
-        else if neighbours[2][2] == ParticleKind::Background { Action::MoveInto{x: 1, y: 1} }
-        else if neighbours[2][0] == ParticleKind::Background { Action::MoveInto{x:-1, y: 1} }
-        // if direct right/left are empty
-        else if neighbours[1][2] == ParticleKind::Background { Action::MoveInto{x: 1, y: 0} }
-        else if neighbours[1][0] == ParticleKind::Background { Action::MoveInto{x:-1, y: 0} }
-        // otherwise, do nothing
-        else {Action::StayPut}
+    fn get_action(&mut self, _: Neighbours, _: &mut Context) -> Action {
+        // wood stays where it's put, until it burns
+        Action::StayPut
     }
     fn get_type(&self) -> ParticleKind {
         ParticleKind::Wood
@@ -25,7 +17,21 @@ impl Particle for WoodParticle {
     fn get_colour(&self) -> Colour {
         BROWN
     }
-    fn tick(&self){
+    fn tick(&mut self){
+        // do nothing
+    }
+}
+
+impl Processable for WoodParticle {
+    fn get_was_processed(&self) -> bool {
+        true
+    }
+
+    fn set_processed(&mut self) {
+        // do nothing
+    }
 
+    fn set_not_processed(&mut self) {
+        // do nothing
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,2 @@
-#[derive(Clone, Hash, std::cmp::Eq)]
+#[derive(Clone, Hash, PartialEq, Eq)]
 pub struct Point(pub usize, pub usize);
-
-impl PartialEq for Point {
-    fn eq(&self, other: &Self) -> bool {
-        (self.0 == other.0) && (self.1 == other.1)
-    }
-}
\ No newline at end of file
@@ -0,0 +1,219 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::conflict::ConflictPolicy;
+use super::gravity::Gravity;
+use super::kinds::ParticleKind;
+use super::life::Rule;
+use super::particles::cornucopia;
+
+const HEADER: &str = "minautomata-recording 1";
+
+/// Something the player did which changed the world.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputEvent {
+    Paint{x: usize, y: usize},
+    SetBrush(ParticleKind),
+    Toggle{x: usize, y: usize},
+    SetSource{kind: ParticleKind, rate: f32},
+    SetCornucopia(cornucopia::Settings),
+    SetLifeRule(Rule),
+    SetSynchronous(bool),
+    SetConflictPolicy(ConflictPolicy),
+    SetGravity(Gravity),
+    SetWindBrush{x: f32, y: f32},
+    SetWind{x: f32, y: f32},
+    AddVortex{x: i32, y: i32, radius: i32, strength: f32}
+}
+
+/// A log of input events, each tagged with the tick at which it happened,
+/// along with everything needed to set up a game in which to replay them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    pub seed: u64,
+    pub canvas_size: usize,
+    /// Tick the game had reached when the recording was taken.
+    pub end_tick: u32,
+    pub events: Vec<(u32, InputEvent)>
+}
+
+impl Recording {
+
+    pub fn new(seed: u64, canvas_size: usize) -> Recording {
+        Recording {
+            seed,
+            canvas_size,
+            end_tick: 0,
+            events: Vec::new()
+        }
+    }
+
+    pub fn push(&mut self, tick: u32, event: InputEvent) {
+        self.events.push((tick, event));
+    }
+}
+
+impl fmt::Display for InputEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputEvent::Paint{x, y} => write!(f, "paint {} {}", x, y),
+            InputEvent::SetBrush(kind) => write!(f, "brush {:?}", kind),
+            InputEvent::Toggle{x, y} => write!(f, "toggle {} {}", x, y),
+            InputEvent::SetSource{kind, rate} => write!(f, "source {:?} {}", kind, rate),
+            InputEvent::SetCornucopia(s) => write!(f, "cornucopia {:?} {} {} {} {:?}",
+                s.kind, s.rate, s.direction.0, s.direction.1, s.reset_by),
+            InputEvent::SetLifeRule(rule) => write!(f, "life {}", rule),
+            InputEvent::SetSynchronous(on) => write!(f, "synchronous {}", on),
+            InputEvent::SetConflictPolicy(policy) => write!(f, "conflicts {:?}", policy),
+            InputEvent::SetGravity(g) => write!(f, "gravity {} {} {}", g.direction.0, g.direction.1, g.strength),
+            InputEvent::SetWindBrush{x, y} => write!(f, "windbrush {} {}", x, y),
+            InputEvent::SetWind{x, y} => write!(f, "wind {} {}", x, y),
+            InputEvent::AddVortex{x, y, radius, strength} => write!(f, "vortex {} {} {} {}", x, y, radius, strength)
+        }
+    }
+}
+
+impl FromStr for InputEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            ["paint", x, y] => Ok(InputEvent::Paint{
+                x: parse_field(x, "x")?,
+                y: parse_field(y, "y")?
+            }),
+            ["brush", name] => parse_kind(name).map(InputEvent::SetBrush),
+            ["toggle", x, y] => Ok(InputEvent::Toggle{
+                x: parse_field(x, "x")?,
+                y: parse_field(y, "y")?
+            }),
+            ["source", name, rate] => Ok(InputEvent::SetSource{
+                kind: parse_kind(name)?,
+                rate: parse_field(rate, "rate")?
+            }),
+            ["cornucopia", name, rate, dx, dy, reset_by] => Ok(InputEvent::SetCornucopia(cornucopia::Settings::new(
+                parse_kind(name)?,
+                parse_field(rate, "rate")?,
+                (parse_field(dx, "dx")?, parse_field(dy, "dy")?),
+                parse_kind(reset_by)?
+            ))),
+            ["life", rule] => rule.parse().map(InputEvent::SetLifeRule),
+            ["synchronous", on] => Ok(InputEvent::SetSynchronous(parse_field(on, "synchronous")?)),
+            ["conflicts", name] => ConflictPolicy::from_name(name)
+                .map(InputEvent::SetConflictPolicy)
+                .ok_or(format!("unknown conflict policy {:?}", name)),
+            ["gravity", x, y, strength] => Ok(InputEvent::SetGravity(Gravity::new(
+                parse_field(x, "x")?,
+                parse_field(y, "y")?,
+                parse_field(strength, "strength")?
+            ))),
+            ["windbrush", x, y] => Ok(InputEvent::SetWindBrush{
+                x: parse_field(x, "x")?,
+                y: parse_field(y, "y")?
+            }),
+            ["wind", x, y] => Ok(InputEvent::SetWind{
+                x: parse_field(x, "x")?,
+                y: parse_field(y, "y")?
+            }),
+            ["vortex", x, y, radius, strength] => Ok(InputEvent::AddVortex{
+                x: parse_field(x, "x")?,
+                y: parse_field(y, "y")?,
+                radius: parse_field(radius, "radius")?,
+                strength: parse_field(strength, "strength")?
+            }),
+            _ => Err(format!("malformed event {:?}", s))
+        }
+    }
+}
+
+/// Serialised as a header, the game settings and then one event per line,
+/// each prefixed by its tick number.
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "canvas {}", self.canvas_size)?;
+        writeln!(f, "end {}", self.end_tick)?;
+        for (tick, event) in self.events.iter() {
+            writeln!(f, "{} {}", tick, event)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
+        if lines.next() != Some(HEADER) {
+            return Err("not a recording".to_string());
+        }
+
+        let seed = parse_field(setting(lines.next(), "seed")?, "seed")?;
+        let canvas_size = parse_field(setting(lines.next(), "canvas")?, "canvas")?;
+        let mut rec = Recording::new(seed, canvas_size);
+        rec.end_tick = parse_field(setting(lines.next(), "end")?, "end")?;
+
+        for line in lines {
+            let (tick, event) = line.split_once(' ').ok_or(format!("malformed event {:?}", line))?;
+            rec.push(parse_field(tick, "tick")?, event.parse()?);
+        }
+        Ok(rec)
+    }
+}
+
+/// Value of a "`name` value" line.
+fn setting<'a>(line: Option<&'a str>, name: &str) -> Result<&'a str, String> {
+    line.and_then(|l| l.strip_prefix(name))
+        .map(str::trim)
+        .ok_or(format!("missing {}", name))
+}
+
+fn parse_kind(name: &str) -> Result<ParticleKind, String> {
+    ParticleKind::from_name(name).ok_or(format!("unknown particle kind {:?}", name))
+}
+
+fn parse_field<T: FromStr>(s: &str, name: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("bad {} {:?}", name, s))
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_round_trip() {
+        let mut rec = Recording::new(7, 64);
+        rec.push(0, InputEvent::SetBrush(ParticleKind::Water));
+        rec.push(3, InputEvent::Paint{x: 10, y: 2});
+        rec.push(3, InputEvent::Paint{x: 11, y: 2});
+        rec.push(5, InputEvent::Toggle{x: 11, y: 2});
+        rec.push(6, InputEvent::SetSource{kind: ParticleKind::Oil, rate: 0.1});
+        rec.push(6, InputEvent::SetCornucopia(cornucopia::Settings::new(
+            ParticleKind::Sand, 0.25, (0, 1), ParticleKind::Water)));
+        rec.push(7, InputEvent::SetLifeRule("B2/S/C3".parse().unwrap()));
+        rec.push(8, InputEvent::SetSynchronous(true));
+        rec.push(8, InputEvent::SetConflictPolicy(ConflictPolicy::Momentum));
+        rec.push(9, InputEvent::SetGravity(Gravity::new(-1.0, -1.0, 0.5)));
+        rec.push(10, InputEvent::SetWind{x: 0.25, y: -1.0});
+        rec.push(10, InputEvent::AddVortex{x: 20, y: 12, radius: 8, strength: -0.5});
+        rec.push(11, InputEvent::SetWindBrush{x: 1.0, y: 0.0});
+        rec.push(11, InputEvent::Paint{x: 3, y: 4});
+        rec.end_tick = 20;
+        let parsed: Recording = rec.to_string().parse().unwrap();
+        assert_eq!(parsed, rec);
+    }
+
+    #[test]
+    pub fn test_bad_input() {
+        assert!("hello".parse::<Recording>().is_err());
+        assert!("paint 1".parse::<InputEvent>().is_err());
+        assert!("brush Lemonade".parse::<InputEvent>().is_err());
+        assert!("source Water lots".parse::<InputEvent>().is_err());
+    }
+
+}
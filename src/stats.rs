@@ -0,0 +1,101 @@
+use wasm_bindgen::prelude::*;
+
+use super::kinds::ParticleKind;
+
+/// Statistics describing the state of the world and what happened during the
+/// most recent tick of the simulation.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Stats {
+    /// Number of ticks simulated so far.
+    pub tick: u32,
+    /// Number of particles which moved during the last tick.
+    pub moves: u32,
+    /// Number of particles which popped during the last tick.
+    pub pops: u32,
+    /// Number of particles grown during the last tick.
+    pub grows: u32,
+    /// Number of particles which changed kind during the last tick.
+    pub becomes: u32,
+    /// Number of reactions between pairs of particles during the last tick.
+    pub reactions: u32,
+    /// Number of blasts set off during the last tick.
+    pub explosions: u32,
+    /// Number of actions abandoned during the last tick because another
+    /// particle had already claimed a cell they would change.
+    pub conflicts: u32,
+    /// Number of moves refused during the last tick because they would have
+    /// overwritten another particle.
+    pub overwrites: u32,
+    /// Number of particles which did something other than stay put during the
+    /// last tick.
+    pub active: u32,
+    /// Wall-clock time taken by the last tick, in milliseconds.
+    pub tick_duration_ms: f64,
+    counts: Vec<u32>
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl Stats {
+
+    pub fn new() -> Stats {
+        Stats {
+            tick: 0,
+            moves: 0,
+            pops: 0,
+            grows: 0,
+            becomes: 0,
+            reactions: 0,
+            explosions: 0,
+            conflicts: 0,
+            overwrites: 0,
+            active: 0,
+            tick_duration_ms: 0.0,
+            counts: vec![0; ParticleKind::ALL.len()]
+        }
+    }
+
+    /// Number of particles of each kind, indexed by `ParticleKind`.
+    #[wasm_bindgen(getter)]
+    pub fn counts(&self) -> Vec<u32> {
+        self.counts.clone()
+    }
+
+    /// Number of particles of the given kind.
+    pub fn count(&self, kind: ParticleKind) -> u32 {
+        self.counts[kind as usize]
+    }
+}
+
+impl Stats {
+
+    /// Forget the per-tick counters, ready for the next tick.
+    pub fn begin_tick(&mut self) {
+        self.moves = 0;
+        self.pops = 0;
+        self.grows = 0;
+        self.becomes = 0;
+        self.reactions = 0;
+        self.explosions = 0;
+        self.conflicts = 0;
+        self.overwrites = 0;
+        self.active = 0;
+    }
+
+    /// Record one particle of `old` kind being replaced by one of `new` kind.
+    pub fn replaced(&mut self, old: ParticleKind, new: ParticleKind) {
+        self.counts[old as usize] -= 1;
+        self.counts[new as usize] += 1;
+    }
+
+    /// Record a particle of `kind` being added without replacing anything.
+    pub fn added(&mut self, kind: ParticleKind) {
+        self.counts[kind as usize] += 1;
+    }
+}
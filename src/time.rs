@@ -0,0 +1,23 @@
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern {
+    /// Bring the external function "performance.now" into rust.
+    #[wasm_bindgen(js_namespace = performance)]
+    fn now() -> f64;
+}
+
+/// Current time in milliseconds, relative to some fixed point in the past.
+#[cfg(target_arch = "wasm32")]
+pub fn now_ms() -> f64 {
+    now()
+}
+
+/// Current time in milliseconds, relative to some fixed point in the past.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_ms() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+}
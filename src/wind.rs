@@ -0,0 +1,136 @@
+use super::colour::Colour;
+use super::kinds::{ParticleKind, Phase};
+
+/// Radius, in cells, of the patch of wind the wind brush paints.
+pub const BRUSH_RADIUS: i32 = 3;
+
+/// Wind speed drawn at full strength in the overlay.
+pub const OVERLAY_SPEED: f32 = 1.0;
+
+/// How much of the overlay colour is mixed into a cell in full wind.
+pub const OVERLAY_AMOUNT: f32 = 0.6;
+
+/// How easily wind moves a particle of `kind`, from 0 (not at all) to 1.
+/// Gases go wherever the wind takes them; powders and liquids less so the
+/// denser they are, and solids stay where they are.
+pub fn catch(kind: ParticleKind) -> f32 {
+    match kind.phase() {
+        Phase::Gas => 1.0,
+        Phase::Powder | Phase::Liquid => (0.5 / kind.density()).min(1.0),
+        _ => 0.0
+    }
+}
+
+/// Chance that wind of velocity `v` moves a particle of `kind` this tick.
+pub fn chance(kind: ParticleKind, v: (f32, f32)) -> f32 {
+    ((v.0*v.0 + v.1*v.1).sqrt() * catch(kind)).min(1.0)
+}
+
+/// Set every cell of `field` blowing at `v`.
+pub fn uniform(field: &mut [(f32, f32)], v: (f32, f32)) {
+    field.fill(v);
+}
+
+/// Add a whirlwind, turning clockwise on screen if `strength` is positive,
+/// centred on (`x`, `y`). It blows hardest, at `strength`, at the centre and
+/// dies away to nothing `radius` cells out.
+pub fn vortex(field: &mut [(f32, f32)], n_cols: usize, x: i32, y: i32, radius: i32, strength: f32) {
+    for_cells_within(field.len(), n_cols, x, y, radius, |idx, dx, dy, d| {
+        if d > 0.0 {
+            let s = strength * (1.0 - d / radius as f32);
+            field[idx].0 -= dy as f32 / d * s;
+            field[idx].1 += dx as f32 / d * s;
+        }
+    });
+}
+
+/// Set the wind within `BRUSH_RADIUS` of (`x`, `y`) blowing at `v`.
+pub fn paint(field: &mut [(f32, f32)], n_cols: usize, x: i32, y: i32, v: (f32, f32)) {
+    for_cells_within(field.len(), n_cols, x, y, BRUSH_RADIUS, |idx, _, _, _| field[idx] = v);
+}
+
+/// Colour to draw a cell of colour `colour` with wind `v` blowing through it:
+/// tinted by which way it's blowing, more so the harder it blows.
+pub fn overlay(colour: &Colour, v: (f32, f32)) -> Colour {
+    let speed = (v.0*v.0 + v.1*v.1).sqrt();
+    if speed == 0.0 {
+        return colour.clone();
+    }
+    let tint = Colour::new(
+        (128.0 + 127.0 * v.0 / speed) as u8,
+        (128.0 + 127.0 * v.1 / speed) as u8,
+        255
+    );
+    colour.mix(&tint, OVERLAY_AMOUNT * (speed / OVERLAY_SPEED).min(1.0))
+}
+
+/// Call `f` with the index of, offset to and distance to each cell of a
+/// world of `len` cells within `radius` of (`x`, `y`).
+fn for_cells_within<F>(len: usize, n_cols: usize, x: i32, y: i32, radius: i32, mut f: F)
+where F: FnMut(usize, i32, i32, f32) {
+    let n_rows = (len / n_cols) as i64;
+    let (x, y, radius) = (x as i64, y as i64, radius as i64);
+    // only look at the part of the circle that's in the world
+    for dy in (-radius).max(-y)..=radius.min(n_rows - 1 - y) {
+        for dx in (-radius).max(-x)..=radius.min(n_cols as i64 - 1 - x) {
+            let d = ((dx*dx + dy*dy) as f32).sqrt();
+            if d > radius as f32 {
+                continue;
+            }
+            f(((y + dy) * n_cols as i64 + x + dx) as usize, dx as i32, dy as i32, d);
+        }
+    }
+}
+
+
+#[allow(dead_code,unused_imports)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_catch() {
+        assert_eq!(catch(ParticleKind::Smoke), 1.0);
+        assert_eq!(catch(ParticleKind::Concrete), 0.0);
+        assert!(catch(ParticleKind::Snow) > catch(ParticleKind::Oil));
+        assert!(catch(ParticleKind::Oil) > catch(ParticleKind::Water));
+        assert!(catch(ParticleKind::Water) > catch(ParticleKind::Sand));
+        assert_eq!(chance(ParticleKind::Smoke, (3.0, 4.0)), 1.0);
+        assert_eq!(chance(ParticleKind::Sand, (0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    pub fn test_vortex() {
+        let mut field = vec![(0.0, 0.0); 100];
+        vortex(&mut field, 10, 5, 5, 4, 1.0);
+        // right of the centre it blows down, and left of it up
+        assert!(field[5*10 + 7].1 > 0.0);
+        assert!(field[5*10 + 3].1 < 0.0);
+        // below it blows left
+        assert!(field[7*10 + 5].0 < 0.0);
+        assert_eq!(field[5*10 + 5], (0.0, 0.0));
+        assert_eq!(field[0], (0.0, 0.0));
+    }
+
+    #[test]
+    pub fn test_huge_vortex() {
+        let mut field = vec![(0.0, 0.0); 100];
+        vortex(&mut field, 10, 5, 5, 50000, 1.0);
+        vortex(&mut field, 10, -100000, 5, i32::MAX, 1.0);
+        assert!(field.iter().all(|v| v.0.is_finite() && v.1.is_finite()));
+        assert!(field[5*10 + 7].1 > 0.0);
+        paint(&mut field, 10, 5000, 5000, (1.0, 0.0));
+        assert!(!field.contains(&(1.0, 0.0)));
+    }
+
+    #[test]
+    pub fn test_paint() {
+        let mut field = vec![(0.0, 0.0); 100];
+        paint(&mut field, 10, 0, 0, (1.0, 0.0));
+        assert_eq!(field[0], (1.0, 0.0));
+        assert_eq!(field[2*10 + 2], (1.0, 0.0));
+        assert_eq!(field[3*10 + 3], (0.0, 0.0));
+        uniform(&mut field, (0.0, -0.5));
+        assert!(field.iter().all(|v| *v == (0.0, -0.5)));
+    }
+}